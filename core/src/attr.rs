@@ -1,36 +1,431 @@
+use std::str::FromStr;
 use structmeta::StructMeta;
 use syn::LitStr;
+use syn::spanned::Spanned;
+use syn::meta::ParseNestedMeta;
+use crate::parse_error::ParseError;
 
-/// Available attributes on a struct
+/// Available attributes on a field or enum variant
 #[derive(StructMeta, Default)]
 pub struct OpenApiAttributes {
     pub skip: bool,
-    pub skip_serializing_if: Option<LitStr>,
+    /// Only in responses, like a server-generated `id`.
+    pub read_only: bool,
+    /// Only in requests, like a `password`.
+    pub write_only: bool,
+    /// Also set by `#[deprecated]`.
+    pub deprecated: bool,
+    /// `#[openapi(default = ..)]`, the value to render as the schema's `default`. Any expression
+    /// that implements `Serialize` works.
+    pub default: Option<syn::Expr>,
+    pub title: Option<LitStr>,
+    /// Overrides the doc comment.
+    pub description: Option<LitStr>,
+    /// `#[openapi(as = Type)]`, document the field as if it had this type.
+    pub r#as: Option<syn::Type>,
+    /// `#[openapi(schema_with = "path::to::fn")]`, a function returning the field's `Schema`.
+    pub schema_with: Option<LitStr>,
+    /// `#[openapi(example = ..)]`, any expression that implements `Serialize`.
+    pub example: Option<syn::Expr>,
+    /// `#[openapi(example_fn = "path::to::fn")]`, a function returning a value to serialize as
+    /// the example.
+    pub example_fn: Option<LitStr>,
+
+    // Validation constraints. Numbers and `pattern` are expressions, so they can be negative or
+    // refer to constants. See [`crate::Constraints`].
+    pub min_length: Option<syn::Expr>,
+    pub max_length: Option<syn::Expr>,
+    pub pattern: Option<syn::Expr>,
+    pub format: Option<LitStr>,
+    pub minimum: Option<syn::Expr>,
+    pub maximum: Option<syn::Expr>,
+    pub exclusive_minimum: Option<syn::Expr>,
+    pub exclusive_maximum: Option<syn::Expr>,
+    pub multiple_of: Option<syn::Expr>,
+    pub min_items: Option<syn::Expr>,
+    pub max_items: Option<syn::Expr>,
+    pub unique_items: bool,
+}
+
+impl OpenApiAttributes {
+    /// Fill in validation constraints from the `#[validate(..)]` attributes of the `validator`
+    /// crate. Constraints set with `#[openapi(..)]` take precedence.
+    ///
+    /// `length` sets both the string and array constraints, since it applies to either. Rules
+    /// without an OpenAPI equivalent, like `custom`, are ignored.
+    pub fn add_validate_attributes(&mut self, attrs: &[syn::Attribute]) -> syn::Result<()> {
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("validate")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("length") {
+                    let (mut min, mut max) = (None, None);
+                    meta.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("min") {
+                            min = Some(parse_validate_value(&meta)?);
+                        } else if meta.path.is_ident("max") {
+                            max = Some(parse_validate_value(&meta)?);
+                        } else if meta.path.is_ident("equal") {
+                            let equal = parse_validate_value(&meta)?;
+                            min = Some(equal.clone());
+                            max = Some(equal);
+                        } else {
+                            skip_meta(&meta)?;
+                        }
+                        Ok(())
+                    })?;
+                    self.min_length = self.min_length.take().or(min.clone());
+                    self.max_length = self.max_length.take().or(max.clone());
+                    self.min_items = self.min_items.take().or(min);
+                    self.max_items = self.max_items.take().or(max);
+                } else if meta.path.is_ident("range") {
+                    let mut range = OpenApiAttributes::default();
+                    meta.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("min") {
+                            range.minimum = Some(parse_validate_value(&meta)?);
+                        } else if meta.path.is_ident("max") {
+                            range.maximum = Some(parse_validate_value(&meta)?);
+                        } else if meta.path.is_ident("exclusive_min") {
+                            range.exclusive_minimum = Some(parse_validate_value(&meta)?);
+                        } else if meta.path.is_ident("exclusive_max") {
+                            range.exclusive_maximum = Some(parse_validate_value(&meta)?);
+                        } else {
+                            skip_meta(&meta)?;
+                        }
+                        Ok(())
+                    })?;
+                    self.minimum = self.minimum.take().or(range.minimum);
+                    self.maximum = self.maximum.take().or(range.maximum);
+                    self.exclusive_minimum = self.exclusive_minimum.take().or(range.exclusive_minimum);
+                    self.exclusive_maximum = self.exclusive_maximum.take().or(range.exclusive_maximum);
+                } else if meta.path.is_ident("email") {
+                    self.format.get_or_insert_with(|| LitStr::new("email", meta.path.span()));
+                    skip_meta(&meta)?;
+                } else if meta.path.is_ident("url") {
+                    self.format.get_or_insert_with(|| LitStr::new("uri", meta.path.span()));
+                    skip_meta(&meta)?;
+                } else if meta.path.is_ident("regex") {
+                    // Either `regex = "RE"` or `regex(path = RE)`, where `RE` derefs to a `Regex`.
+                    let mut regex = None;
+                    if meta.input.peek(syn::Token![=]) {
+                        regex = Some(parse_validate_value(&meta)?);
+                    } else {
+                        meta.parse_nested_meta(|meta| {
+                            if meta.path.is_ident("path") {
+                                regex = Some(parse_validate_value(&meta)?);
+                            } else {
+                                skip_meta(&meta)?;
+                            }
+                            Ok(())
+                        })?;
+                    }
+                    if let (None, Some(regex)) = (&self.pattern, regex) {
+                        self.pattern = Some(syn::parse_quote!((#regex).as_str()));
+                    }
+                } else {
+                    skip_meta(&meta)?;
+                }
+                Ok(())
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a value of a `#[validate(..)]` attribute. Older versions of `validator` take paths to
+/// constants as strings, like `min = "MIN_LEN"`, so those are parsed as expressions.
+fn parse_validate_value(meta: &ParseNestedMeta) -> syn::Result<syn::Expr> {
+    let value: syn::Expr = meta.value()?.parse()?;
+    match &value {
+        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) => s.parse(),
+        _ => Ok(value),
+    }
+}
+
+/// When a key appears in several `#[openapi(..)]` attributes, the last one wins.
+macro_rules! merge {
+    ($result:ident, $attr:ident, flags: [$($flag:ident),*], values: [$($value:ident),*]) => {
+        $(
+            if $attr.$flag {
+                $result.$flag = true;
+            }
+        )*
+        $(
+            if $attr.$value.is_some() {
+                $result.$value = $attr.$value;
+            }
+        )*
+    };
 }
 
 impl TryFrom<&Vec<syn::Attribute>> for OpenApiAttributes {
     type Error = syn::Error;
 
     fn try_from(attrs: &Vec<syn::Attribute>) -> Result<Self, Self::Error> {
-        let mut attrs = attrs.into_iter().filter_map(|attr| {
-            attr.path().get_ident().and_then(|ident| {
-                if ident == "openapi" || ident == "serde" {
-                    let attrs: OpenApiAttributes = attr.parse_args().ok()?;
-                    Some(attrs)
+        let mut result = OpenApiAttributes::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("openapi")) {
+            let attr: OpenApiAttributes = attr.parse_args()?;
+            merge!(result, attr,
+                flags: [skip, read_only, write_only, deprecated, unique_items],
+                values: [default, title, description, r#as, schema_with, example, example_fn, min_length, max_length, pattern, format,
+                    minimum, maximum, exclusive_minimum, exclusive_maximum, multiple_of, min_items, max_items]
+            );
+        }
+        Ok(result)
+    }
+}
+
+/// Available attributes on the type itself, as opposed to its fields
+#[derive(StructMeta, Default)]
+pub struct OpenApiContainerAttributes {
+    /// On a newtype struct, reuse the inner type's schema instead of registering a component
+    /// that wraps it.
+    pub inline: bool,
+    /// Also set by `#[deprecated]`.
+    pub deprecated: bool,
+    pub title: Option<LitStr>,
+    /// Overrides the doc comment.
+    pub description: Option<LitStr>,
+    /// `#[openapi(rename = "..")]`, the component name. Unlike `#[serde(rename)]`, it doesn't
+    /// change the JSON, and it isn't qualified with the module path.
+    pub rename: Option<LitStr>,
+    /// `#[openapi(remote = "other_crate::Type")]`, the type is a local mirror of a type from
+    /// another crate, and documents that type. Also set by `#[serde(remote = "..")]`.
+    pub remote: Option<LitStr>,
+    /// `#[openapi(as = Type)]`, use another type's schema for this one.
+    pub r#as: Option<syn::Type>,
+    /// `#[openapi(schema_with = "path::to::fn")]`, a function returning the type's `Schema`.
+    pub schema_with: Option<LitStr>,
+    /// Same as on fields.
+    pub example: Option<syn::Expr>,
+    /// Same as on fields.
+    pub example_fn: Option<LitStr>,
+    /// `#[openapi(additional_properties = false)]`, whether a struct accepts keys beyond its
+    /// fields. Defaults to `false` with `#[serde(deny_unknown_fields)]`, and unset otherwise.
+    pub additional_properties: Option<syn::LitBool>,
+}
+
+impl TryFrom<&Vec<syn::Attribute>> for OpenApiContainerAttributes {
+    type Error = syn::Error;
+
+    fn try_from(attrs: &Vec<syn::Attribute>) -> Result<Self, Self::Error> {
+        let mut result = OpenApiContainerAttributes::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("openapi")) {
+            let attr: OpenApiContainerAttributes = attr.parse_args()?;
+            merge!(result, attr, flags: [inline, deprecated], values: [title, description, rename, remote, r#as, schema_with, example, example_fn, additional_properties]);
+        }
+        Ok(result)
+    }
+}
+
+/// The subset of `#[serde(..)]` attributes that changes what the JSON looks like.
+///
+/// Serde accepts many more keys than we care about, so unknown keys are skipped over instead of
+/// failing the whole attribute. Where serde allows separate `serialize` and `deserialize` values,
+/// such as `rename(serialize = "..", deserialize = "..")`, the schema uses the serialized form.
+/// `alias` only affects deserialization, so it doesn't change the schema.
+#[derive(Default)]
+pub struct SerdeAttributes {
+    pub rename: Option<String>,
+    pub rename_all: Option<RenameRule>,
+    /// `#[serde(rename_all_fields = "..")]` on an enum, which renames the fields of its struct variants.
+    pub rename_all_fields: Option<RenameRule>,
+    pub skip: bool,
+    pub skip_serializing: bool,
+    pub skip_deserializing: bool,
+    pub skip_serializing_if: Option<String>,
+    /// `#[serde(tag = "..")]`, for internally and adjacently tagged enums.
+    pub tag: Option<String>,
+    /// `#[serde(content = "..")]`, for adjacently tagged enums.
+    pub content: Option<String>,
+    pub untagged: bool,
+    pub transparent: bool,
+    pub flatten: bool,
+    pub deny_unknown_fields: bool,
+    /// `#[serde(default)]` or `#[serde(default = "..")]`, on a struct or a field.
+    pub default: bool,
+    /// `#[serde(remote = "..")]`, the path of the type a local mirror stands in for.
+    pub remote: Option<String>,
+}
+
+impl TryFrom<&Vec<syn::Attribute>> for SerdeAttributes {
+    type Error = syn::Error;
+
+    fn try_from(attrs: &Vec<syn::Attribute>) -> Result<Self, Self::Error> {
+        let mut result = SerdeAttributes::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    if let Some(rename) = parse_serialize_value(&meta)? {
+                        result.rename = Some(rename.value());
+                    }
+                } else if meta.path.is_ident("rename_all") {
+                    if let Some(rule) = parse_serialize_value(&meta)? {
+                        result.rename_all = Some(parse_rename_rule(&rule)?);
+                    }
+                } else if meta.path.is_ident("rename_all_fields") {
+                    if let Some(rule) = parse_serialize_value(&meta)? {
+                        result.rename_all_fields = Some(parse_rename_rule(&rule)?);
+                    }
+                } else if meta.path.is_ident("skip") {
+                    result.skip = true;
+                } else if meta.path.is_ident("skip_serializing") {
+                    result.skip_serializing = true;
+                } else if meta.path.is_ident("skip_deserializing") {
+                    result.skip_deserializing = true;
+                } else if meta.path.is_ident("skip_serializing_if") {
+                    let s: LitStr = meta.value()?.parse()?;
+                    result.skip_serializing_if = Some(s.value());
+                } else if meta.path.is_ident("tag") {
+                    let s: LitStr = meta.value()?.parse()?;
+                    result.tag = Some(s.value());
+                } else if meta.path.is_ident("content") {
+                    let s: LitStr = meta.value()?.parse()?;
+                    result.content = Some(s.value());
+                } else if meta.path.is_ident("untagged") {
+                    result.untagged = true;
+                } else if meta.path.is_ident("transparent") {
+                    result.transparent = true;
+                } else if meta.path.is_ident("flatten") {
+                    result.flatten = true;
+                } else if meta.path.is_ident("deny_unknown_fields") {
+                    result.deny_unknown_fields = true;
+                } else if meta.path.is_ident("remote") {
+                    let s: LitStr = meta.value()?.parse()?;
+                    result.remote = Some(s.value());
+                } else if meta.path.is_ident("default") {
+                    result.default = true;
+                    skip_meta(&meta)?;
                 } else {
-                    None
+                    skip_meta(&meta)?;
                 }
-            })
-        });
-        let mut result = attrs.next().unwrap_or_default();
-        for attr in attrs {
-            if attr.skip {
-                result.skip = true;
+                Ok(())
+            })?;
+        }
+        Ok(result)
+    }
+}
+
+impl SerdeAttributes {
+    /// Whether the field or variant is left out of the schema entirely.
+    pub fn is_skipped(&self) -> bool {
+        self.skip || (self.skip_serializing && self.skip_deserializing)
+    }
+}
+
+/// Parse the serialize side of `key = ".."` or `key(serialize = "..", deserialize = "..")`.
+fn parse_serialize_value(meta: &ParseNestedMeta) -> syn::Result<Option<LitStr>> {
+    if meta.input.peek(syn::Token![=]) {
+        return Ok(Some(meta.value()?.parse()?));
+    }
+    let mut result = None;
+    meta.parse_nested_meta(|meta| {
+        if meta.path.is_ident("serialize") {
+            result = Some(meta.value()?.parse()?);
+        } else {
+            skip_meta(&meta)?;
+        }
+        Ok(())
+    })?;
+    Ok(result)
+}
+
+fn parse_rename_rule(s: &LitStr) -> syn::Result<RenameRule> {
+    s.value().parse().map_err(|e| syn::Error::new(s.span(), e))
+}
+
+/// Consume the value of a meta item we don't interpret, whether it's `key`, `key = value`,
+/// or `key(..)`.
+fn skip_meta(meta: &ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|meta| skip_meta(&meta))?;
+    }
+    Ok(())
+}
+
+/// The casing conventions serde supports in `rename_all`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RenameRule {
+    LowerCase,
+    UpperCase,
+    PascalCase,
+    CamelCase,
+    SnakeCase,
+    ScreamingSnakeCase,
+    KebabCase,
+    ScreamingKebabCase,
+}
+
+impl FromStr for RenameRule {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "lowercase" => RenameRule::LowerCase,
+            "UPPERCASE" => RenameRule::UpperCase,
+            "PascalCase" => RenameRule::PascalCase,
+            "camelCase" => RenameRule::CamelCase,
+            "snake_case" => RenameRule::SnakeCase,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnakeCase,
+            "kebab-case" => RenameRule::KebabCase,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebabCase,
+            _ => return Err(ParseError(format!("unknown rename rule `rename_all = {:?}`", s))),
+        })
+    }
+}
+
+impl RenameRule {
+    /// Apply the rule to a variant name, which serde assumes is written in PascalCase.
+    pub fn apply_to_variant(&self, variant: &str) -> String {
+        use RenameRule::*;
+        match self {
+            PascalCase => variant.to_string(),
+            LowerCase => variant.to_ascii_lowercase(),
+            UpperCase => variant.to_ascii_uppercase(),
+            CamelCase => variant[..1].to_ascii_lowercase() + &variant[1..],
+            SnakeCase => {
+                let mut snake = String::new();
+                for (i, ch) in variant.char_indices() {
+                    if i > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(ch.to_ascii_lowercase());
+                }
+                snake
+            }
+            ScreamingSnakeCase => SnakeCase.apply_to_variant(variant).to_ascii_uppercase(),
+            KebabCase => SnakeCase.apply_to_variant(variant).replace('_', "-"),
+            ScreamingKebabCase => ScreamingSnakeCase.apply_to_variant(variant).replace('_', "-"),
+        }
+    }
+
+    /// Apply the rule to a field name, which serde assumes is written in snake_case.
+    pub fn apply_to_field(&self, field: &str) -> String {
+        use RenameRule::*;
+        match self {
+            LowerCase | SnakeCase => field.to_string(),
+            UpperCase | ScreamingSnakeCase => field.to_ascii_uppercase(),
+            PascalCase => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(ch);
+                    }
+                }
+                pascal
             }
-            if attr.skip_serializing_if.is_some() {
-                result.skip_serializing_if = attr.skip_serializing_if;
+            CamelCase => {
+                let pascal = PascalCase.apply_to_field(field);
+                pascal[..1].to_ascii_lowercase() + &pascal[1..]
             }
+            KebabCase => field.replace('_', "-"),
+            ScreamingKebabCase => ScreamingSnakeCase.apply_to_field(field).replace('_', "-"),
         }
-        Ok(result)
     }
 }
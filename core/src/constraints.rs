@@ -0,0 +1,125 @@
+use openapiv3::{AnySchema, Schema, SchemaKind, Type};
+use serde_json::Value;
+
+/// Validation keywords to add to a schema, e.g. from `#[openapi(min_length = 1)]`.
+///
+/// Each keyword only applies to the schema types it's defined for, so `max_length` sets
+/// `maxLength` on a string, and is ignored on anything else. A reference to a component, wrapped
+/// in an `allOf` by [`into_schema`](crate::into_schema), doesn't tell its type, so it gets every
+/// keyword next to the `allOf`, and validators apply the ones that fit.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Constraints {
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub pattern: Option<String>,
+    /// Known formats parse into their variant, anything else is kept as is.
+    pub format: Option<String>,
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+    /// OpenAPI 3.0 expresses this as `minimum` with `exclusiveMinimum: true`.
+    pub exclusive_minimum: Option<f64>,
+    /// OpenAPI 3.0 expresses this as `maximum` with `exclusiveMaximum: true`.
+    pub exclusive_maximum: Option<f64>,
+    pub multiple_of: Option<f64>,
+    pub min_items: Option<usize>,
+    pub max_items: Option<usize>,
+    pub unique_items: bool,
+}
+
+impl Constraints {
+    pub fn apply(&self, schema: &mut Schema) {
+        if let SchemaKind::AllOf { all_of } = &mut schema.schema_kind {
+            let all_of = std::mem::take(all_of);
+            schema.schema_kind = SchemaKind::Any(AnySchema { all_of, ..AnySchema::default() });
+        }
+        let ty = match &mut schema.schema_kind {
+            SchemaKind::Type(ty) => ty,
+            SchemaKind::Any(any) => return self.apply_any(any),
+            _ => return,
+        };
+        match ty {
+            Type::String(s) => {
+                s.min_length = self.min_length.or(s.min_length);
+                s.max_length = self.max_length.or(s.max_length);
+                if let Some(pattern) = &self.pattern {
+                    s.pattern = Some(pattern.clone());
+                }
+                if let Some(format) = &self.format {
+                    s.format = serde_json::from_value(Value::String(format.clone())).unwrap();
+                }
+            }
+            Type::Number(n) => {
+                n.multiple_of = self.multiple_of.or(n.multiple_of);
+                if let Some((minimum, exclusive)) = self.minimum() {
+                    n.minimum = Some(minimum);
+                    n.exclusive_minimum = exclusive;
+                }
+                if let Some((maximum, exclusive)) = self.maximum() {
+                    n.maximum = Some(maximum);
+                    n.exclusive_maximum = exclusive;
+                }
+                if let Some(format) = &self.format {
+                    n.format = serde_json::from_value(Value::String(format.clone())).unwrap();
+                }
+            }
+            Type::Integer(i) => {
+                if let Some(multiple_of) = self.multiple_of {
+                    assert!(
+                        multiple_of.fract() == 0.0 && multiple_of >= 1.0,
+                        "multiple_of = {} can't apply to an integer, which needs a positive whole number.",
+                        multiple_of,
+                    );
+                    i.multiple_of = Some(multiple_of as i64);
+                }
+                // A fractional bound is rounded inwards, to the closest integer it allows, which
+                // also makes an exclusive one inclusive.
+                if let Some((minimum, exclusive)) = self.minimum() {
+                    i.minimum = Some(minimum.ceil() as i64);
+                    i.exclusive_minimum = exclusive && minimum.fract() == 0.0;
+                }
+                if let Some((maximum, exclusive)) = self.maximum() {
+                    i.maximum = Some(maximum.floor() as i64);
+                    i.exclusive_maximum = exclusive && maximum.fract() == 0.0;
+                }
+                if let Some(format) = &self.format {
+                    i.format = serde_json::from_value(Value::String(format.clone())).unwrap();
+                }
+            }
+            Type::Array(a) => {
+                a.min_items = self.min_items.or(a.min_items);
+                a.max_items = self.max_items.or(a.max_items);
+                a.unique_items |= self.unique_items;
+            }
+            Type::Object(_) | Type::Boolean {} => {}
+        }
+    }
+
+    fn apply_any(&self, any: &mut AnySchema) {
+        any.min_length = self.min_length.or(any.min_length);
+        any.max_length = self.max_length.or(any.max_length);
+        any.pattern = self.pattern.clone().or(any.pattern.take());
+        any.format = self.format.clone().or(any.format.take());
+        any.multiple_of = self.multiple_of.or(any.multiple_of);
+        if let Some((minimum, exclusive)) = self.minimum() {
+            any.minimum = Some(minimum);
+            any.exclusive_minimum = exclusive.then_some(true);
+        }
+        if let Some((maximum, exclusive)) = self.maximum() {
+            any.maximum = Some(maximum);
+            any.exclusive_maximum = exclusive.then_some(true);
+        }
+        any.min_items = self.min_items.or(any.min_items);
+        any.max_items = self.max_items.or(any.max_items);
+        if self.unique_items {
+            any.unique_items = Some(true);
+        }
+    }
+
+    fn minimum(&self) -> Option<(f64, bool)> {
+        self.exclusive_minimum.map(|m| (m, true)).or(self.minimum.map(|m| (m, false)))
+    }
+
+    fn maximum(&self) -> Option<(f64, bool)> {
+        self.exclusive_maximum.map(|m| (m, true)).or(self.maximum.map(|m| (m, false)))
+    }
+}
@@ -1,12 +1,22 @@
 mod operation;
 mod schema;
 mod attr;
+mod registry;
 mod parse_error;
+mod constraints;
+mod path;
+mod query;
 
 pub use operation::*;
 pub use schema::*;
 pub use attr::*;
+pub use registry::*;
+pub use constraints::*;
+pub use path::*;
+pub use query::*;
 pub use openapiv3::*;
+// Used by generated code.
+pub use serde_json;
 
 #[cfg(test)]
 #[allow(non_snake_case)]
@@ -19,4 +29,27 @@ mod tests {
         let s = String::schema().unwrap();
         let SchemaKind::Type(openapiv3::Type::String(_)) = s.schema_kind else { panic!() };
     }
+
+    #[test]
+    fn test_rename_rule() {
+        assert_eq!(RenameRule::SnakeCase.apply_to_variant("PendingReview"), "pending_review");
+        assert_eq!(RenameRule::ScreamingKebabCase.apply_to_variant("PendingReview"), "PENDING-REVIEW");
+        assert_eq!(RenameRule::CamelCase.apply_to_field("found_account"), "foundAccount");
+        assert_eq!(RenameRule::PascalCase.apply_to_field("found_account"), "FoundAccount");
+    }
+
+    #[test]
+    fn test_type_name_part() {
+        assert_eq!(String::type_name_part(), "String");
+        assert_eq!(<Vec<Option<i32>>>::type_name_part(), "Vec_Option_i32");
+        assert_eq!(<Option<Box<i32>>>::type_name_part(), "Option_i32");
+        assert_eq!(<Option<Box<i32>>>::schema_owner(), <Option<i32>>::schema_owner());
+    }
+
+    #[test]
+    #[should_panic(expected = "multiple_of = 0.5 can't apply to an integer")]
+    fn test_fractional_multiple_of_on_integer() {
+        let mut schema = i32::schema().unwrap();
+        Constraints { multiple_of: Some(0.5), ..Constraints::default() }.apply(&mut schema);
+    }
 }
@@ -6,7 +6,7 @@ use std::task::{Context, Poll};
 use openapiv3::{MediaType, Operation, ReferenceOr, RequestBody, Response, Responses, StatusCode};
 use pin_project_lite::pin_project;
 use openapiv3 as oa;
-use crate::OaSchema;
+use crate::{OaSchema, SchemaRegistry};
 
 
 fn type_name_to_operation_id(type_name: &str) -> Option<String> {
@@ -14,10 +14,9 @@ fn type_name_to_operation_id(type_name: &str) -> Option<String> {
 }
 
 pub trait OaOperation<Signature> {
-    /// Allows looking up the schema itself of a referenced schema
-    fn referenced_schema(schema: &str) -> oa::Schema;
-    /// List of all schemas referenced by this operation
-    fn references() -> Vec<&'static str>;
+    /// Adds the component schemas the arguments and response rely on to the registry, including
+    /// the schemas those rely on in turn.
+    fn register_schemas(registry: &mut SchemaRegistry);
     /// Constructs the operation
     fn operation() -> Operation;
 }
@@ -79,21 +78,9 @@ macro_rules! construct_operation {
                 Fut::Output: OaSchema,
                 FuncMetadata: FunctionMetadata,
         {
-            fn referenced_schema(schema: &str) -> oa::Schema {
-                $(
-                    if $arg::schema_name().map(|s| s == schema).unwrap_or(false) {
-                        return $arg::schema().expect(&format!("Schema not found: {}", schema));
-                    }
-                )+
-                if Fut::Output::schema_name().map(|s| s == schema).unwrap_or(false) {
-                    Fut::Output::schema().expect(&format!("Schema not found: {}", schema))
-                } else {
-                    panic!("Unknown schema: {}", schema)
-                }
-            }
-
-            fn references() -> Vec<&'static str> {
-                vec![$($arg::schema_name()),+, Fut::Output::schema_name()].into_iter().flatten().collect()
+            fn register_schemas(registry: &mut SchemaRegistry) {
+                $( $arg::register(registry); )+
+                Fut::Output::register(registry);
             }
 
             fn operation() -> Operation {
@@ -0,0 +1,51 @@
+use openapiv3::{Operation, Parameter, ReferenceOr};
+
+/// The names of the `{placeholders}` in a route, in order.
+pub fn path_placeholders(path: &str) -> Vec<&str> {
+    path.split('{')
+        .skip(1)
+        .filter_map(|s| s.split_once('}'))
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// Name the path parameters of an operation after the placeholders of its route.
+///
+/// Parameters from structs are already named, and must match a placeholder. Unnamed ones, like
+/// those from tuples, take the names of the remaining placeholders in order. Handlers without path
+/// parameters are left alone, as they may read the path some other way.
+///
+/// # Panics
+///
+/// If a named parameter has no placeholder, or the number of unnamed parameters differs from the
+/// number of placeholders left for them.
+pub fn bind_path_parameters(path: &str, operation: &mut Operation) {
+    let mut parameters = operation.parameters.iter_mut()
+        .filter_map(|p| match p {
+            ReferenceOr::Item(Parameter::Path { parameter_data, .. }) => Some(parameter_data),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if parameters.is_empty() {
+        return;
+    }
+    let placeholders = path_placeholders(path);
+    for parameter in parameters.iter().filter(|p| !p.name.is_empty()) {
+        if !placeholders.contains(&parameter.name.as_str()) {
+            panic!("Path parameter `{}` has no placeholder in route `{}`.", parameter.name, path);
+        }
+    }
+    let remaining = placeholders.iter()
+        .filter(|name| !parameters.iter().any(|p| p.name == **name))
+        .collect::<Vec<_>>();
+    let mut unnamed = parameters.iter_mut().filter(|p| p.name.is_empty()).collect::<Vec<_>>();
+    if unnamed.len() != remaining.len() {
+        panic!(
+            "Route `{}` has {} placeholder(s) for positional path parameters, but the handler extracts {}.",
+            path, remaining.len(), unnamed.len(),
+        );
+    }
+    for (parameter, name) in unnamed.iter_mut().zip(remaining) {
+        parameter.name = name.to_string();
+    }
+}
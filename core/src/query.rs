@@ -0,0 +1,150 @@
+use indexmap::IndexMap;
+use openapiv3::{Parameter, ParameterData, ParameterSchemaOrContent, QueryStyle, ReferenceOr, Schema, SchemaKind, Type};
+use crate::{OaSchema, SchemaRegistry};
+
+/// The parameters a query string extractor like `Query<T>` reads.
+///
+/// Each field of a struct becomes its own parameter, required unless the field can be left out or
+/// is an `Option`, and described by the field's doc comment. Arrays repeat the parameter for each
+/// item, and nested objects use the `deepObject` style, as in `filter[name]=..`. Other types, like
+/// maps, become a single parameter whose keys are spread across the query string.
+///
+/// The fields of a `#[serde(flatten)]` enum become parameters too, each of them optional, since
+/// they depend on the variant.
+pub fn query_parameters<T: OaSchema>() -> Vec<ReferenceOr<Parameter>> {
+    let Some(schema) = T::schema() else {
+        return Vec::new();
+    };
+    // Fields may refer to components, which tell whether they are objects.
+    let mut registry = SchemaRegistry::new();
+    T::register(&mut registry);
+    let (properties, required) = match schema.schema_kind {
+        SchemaKind::Type(Type::Object(o)) if !o.properties.is_empty() => (o.properties, o.required),
+        // A struct with a flattened enum, whose own fields come first.
+        SchemaKind::AllOf { all_of } if matches!(all_of.first(), Some(ReferenceOr::Item(Schema { schema_kind: SchemaKind::Type(Type::Object(_)), .. }))) => {
+            let mut parts = all_of.into_iter();
+            let Some(ReferenceOr::Item(Schema { schema_kind: SchemaKind::Type(Type::Object(o)), .. })) = parts.next() else {
+                unreachable!();
+            };
+            let mut properties = o.properties;
+            let mut seen = Vec::new();
+            for part in parts {
+                variant_properties(&part, &registry, &mut properties, &mut seen);
+            }
+            (properties, o.required)
+        }
+        schema_kind => {
+            let schema = Schema { schema_data: schema.schema_data, schema_kind };
+            return vec![query_parameter("query", ReferenceOr::Item(schema), false, QueryStyle::Form, Some(true))];
+        }
+    };
+    properties.into_iter()
+        .map(|(name, schema)| {
+            // A missing parameter deserializes to `None`, unlike a missing key in a JSON body.
+            let nullable = schema.as_item().is_some_and(|s| s.schema_data.nullable);
+            let required = required.contains(&name) && !nullable;
+            // `form` already explodes by default, but generators tend to look for `explode: true`.
+            let (style, explode) = match resolve(&schema, &registry).map(|s| &s.schema_kind) {
+                Some(SchemaKind::Type(Type::Object(_))) => (QueryStyle::DeepObject, Some(true)),
+                Some(SchemaKind::Type(Type::Array(_))) => (QueryStyle::Form, Some(true)),
+                _ => (QueryStyle::Form, None),
+            };
+            query_parameter(&name, schema, required, style, explode)
+        })
+        .collect()
+}
+
+/// Add the properties of every variant of a flattened enum to `properties`. A property in several
+/// variants, like the tag of an internally tagged enum, allows the values of each.
+fn variant_properties(schema: &ReferenceOr<Schema>, registry: &SchemaRegistry, properties: &mut IndexMap<String, ReferenceOr<Schema>>, seen: &mut Vec<String>) {
+    let schema = match schema {
+        ReferenceOr::Reference { reference } => {
+            let name = reference.rsplit('/').next().unwrap_or_default();
+            // Recursive enums refer to themselves.
+            if seen.iter().any(|seen| seen == name) {
+                return;
+            }
+            seen.push(name.to_string());
+            match registry.get(name) {
+                Some(schema) => schema,
+                None => return,
+            }
+        }
+        ReferenceOr::Item(schema) => schema,
+    };
+    match &schema.schema_kind {
+        SchemaKind::Type(Type::Object(o)) => {
+            for (name, property) in &o.properties {
+                let Some(existing) = properties.get_mut(name) else {
+                    properties.insert(name.clone(), property.clone());
+                    continue;
+                };
+                let existing = existing.as_mut().map(|s| &mut s.schema_kind);
+                let other = property.as_item().map(|s| &s.schema_kind);
+                let (Some(SchemaKind::Type(Type::String(existing))), Some(SchemaKind::Type(Type::String(other)))) = (existing, other) else {
+                    continue;
+                };
+                if existing.enumeration.is_empty() || other.enumeration.is_empty() {
+                    continue;
+                }
+                for value in &other.enumeration {
+                    if !existing.enumeration.contains(value) {
+                        existing.enumeration.push(value.clone());
+                    }
+                }
+            }
+        }
+        SchemaKind::OneOf { one_of: schemas } | SchemaKind::AnyOf { any_of: schemas } | SchemaKind::AllOf { all_of: schemas } => {
+            for schema in schemas {
+                variant_properties(schema, registry, properties, seen);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The schema a property describes, looking through references and the `allOf` wrapping them.
+fn resolve<'a>(schema: &'a ReferenceOr<Schema>, registry: &'a SchemaRegistry) -> Option<&'a Schema> {
+    match schema {
+        ReferenceOr::Reference { reference } => {
+            registry.get(reference.rsplit('/').next()?)
+        }
+        ReferenceOr::Item(Schema { schema_kind: SchemaKind::AllOf { all_of }, .. }) if all_of.len() == 1 => {
+            resolve(&all_of[0], registry)
+        }
+        // The same, with validation keywords next to the `allOf`.
+        ReferenceOr::Item(Schema { schema_kind: SchemaKind::Any(any), .. }) if any.all_of.len() == 1 => {
+            resolve(&any.all_of[0], registry)
+        }
+        ReferenceOr::Item(schema) => Some(schema),
+    }
+}
+
+fn query_parameter(
+    name: &str,
+    mut schema: ReferenceOr<Schema>,
+    required: bool,
+    style: QueryStyle,
+    explode: Option<bool>,
+) -> ReferenceOr<Parameter> {
+    let description = match &mut schema {
+        ReferenceOr::Item(schema) => schema.schema_data.description.take(),
+        ReferenceOr::Reference { .. } => None,
+    };
+    ReferenceOr::Item(Parameter::Query {
+        parameter_data: ParameterData {
+            name: name.to_string(),
+            description,
+            required,
+            deprecated: None,
+            format: ParameterSchemaOrContent::Schema(schema),
+            example: None,
+            examples: Default::default(),
+            explode,
+            extensions: Default::default(),
+        },
+        allow_reserved: false,
+        style,
+        allow_empty_value: None,
+    })
+}
@@ -0,0 +1,120 @@
+use indexmap::IndexMap;
+use openapiv3::Schema;
+use serde_json::Value;
+use crate::OaSchema;
+
+/// Collects the named component schemas an operation relies on, so they can be added to
+/// `components/schemas` of the spec.
+#[derive(Default, Clone)]
+pub struct SchemaRegistry {
+    schemas: IndexMap<String, Schema>,
+    /// The Rust type that owns each component name, to catch two types sharing a name.
+    owners: IndexMap<String, String>,
+}
+
+impl SchemaRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.schemas.contains_key(name)
+    }
+
+    /// Add a component schema. If a schema is already registered under that name, it's kept.
+    pub fn insert(&mut self, name: &str, schema: Schema) {
+        if !self.contains(name) {
+            self.schemas.insert(name.to_string(), schema);
+        }
+    }
+
+    /// Record `T` as the owner of the component `name`. Returns `false` if `T` already owns it,
+    /// in which case the component was registered before.
+    ///
+    /// # Panics
+    ///
+    /// If another type already owns the name. Their schemas would overwrite each other, so one of
+    /// them needs `#[openapi(rename = "..")]`, or the server needs
+    /// `qualify_component_names`.
+    pub fn claim<T: ?Sized>(&mut self, name: &str) -> bool {
+        self.claim_as(name, std::any::type_name::<T>())
+    }
+
+    /// Like [`Self::claim`], with the owner given by name, e.g. [`OaSchema::schema_owner`].
+    ///
+    /// [`OaSchema::schema_owner`]: crate::OaSchema::schema_owner
+    pub fn claim_as(&mut self, name: &str, owner: &str) -> bool {
+        match self.owners.get(name) {
+            Some(existing) if existing == owner => false,
+            Some(existing) => panic!(
+                "Component name `{}` is used by both `{}` and `{}`. Rename one of them with \
+                #[openapi(rename = \"..\")], or qualify component names with their module path.",
+                name, existing, owner,
+            ),
+            None => {
+                self.owners.insert(name.to_string(), owner.to_string());
+                true
+            }
+        }
+    }
+
+    /// Register the components `T` relies on, but not `T`'s own, for types whose schema is inlined
+    /// into another, like `#[serde(flatten)]` fields. `T`'s component is kept if another component
+    /// refers to it, e.g. because `T` is recursive.
+    pub fn register_dependencies<T: OaSchema + ?Sized>(&mut self) {
+        let name = match T::schema_name() {
+            Some(name) if !self.owners.contains_key(&name) => name,
+            _ => return T::register(self),
+        };
+        T::register(self);
+        let reference = format!("#/components/schemas/{}", name);
+        let referenced = self.schemas.iter()
+            .filter(|(other, _)| **other != name)
+            .any(|(_, schema)| refers_to(&serde_json::to_value(schema).unwrap(), &reference));
+        if !referenced {
+            self.schemas.shift_remove(&name);
+            self.owners.shift_remove(&name);
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Schema> {
+        self.schemas.get(name)
+    }
+
+    /// The name of the Rust type that owns the component `name`, as given by
+    /// `std::any::type_name`.
+    pub fn owner(&self, name: &str) -> Option<&str> {
+        self.owners.get(name).map(String::as_str)
+    }
+
+    /// The names of claimed components. Unlike [`Self::iter`], this includes those claimed without
+    /// registering a schema, like the ones of [`Remote`](crate::Remote) types.
+    pub fn claimed(&self) -> impl Iterator<Item = &str> {
+        self.owners.keys().map(String::as_str)
+    }
+
+    pub fn iter(&self) -> indexmap::map::Iter<'_, String, Schema> {
+        self.schemas.iter()
+    }
+}
+
+impl IntoIterator for SchemaRegistry {
+    type Item = (String, Schema);
+    type IntoIter = indexmap::map::IntoIter<String, Schema>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.schemas.into_iter()
+    }
+}
+
+/// Whether a serialized schema contains a `$ref` to `reference`.
+fn refers_to(value: &Value, reference: &str) -> bool {
+    match value {
+        Value::Object(map) => {
+            map.get("$ref").and_then(Value::as_str) == Some(reference)
+                || map.values().any(|value| refers_to(value, reference))
+        }
+        Value::Array(items) => items.iter().any(|value| refers_to(value, reference)),
+        _ => false,
+    }
+}
@@ -1,5 +1,7 @@
 use openapiv3 as oa;
-use openapiv3::{Schema, SchemaKind, SchemaData, ArrayType, Type, ReferenceOr};
+use openapiv3::{Schema, SchemaKind, SchemaData, ArrayType, ObjectType, StringType, IntegerType, IntegerFormat, NumberType, NumberFormat, Type, ReferenceOr, AdditionalProperties, VariantOrUnknownOrEmpty};
+use std::cell::Cell;
+use crate::SchemaRegistry;
 
 #[cfg(feature = "actix")]
 mod actix;
@@ -21,12 +23,38 @@ mod phonenumber;
 mod http;
 #[cfg(feature = "sid")]
 mod sid;
+mod remote;
+
+pub use remote::*;
 
 pub trait OaSchema {
-    fn schema_name() -> Option<&'static str> {
+    /// The name of the type's component in `components/schemas`, if it has one.
+    ///
+    /// The names of derived types depend on whether they are qualified with their module path,
+    /// which is set for the current thread by [`with_qualified_names`]. A server that qualifies
+    /// names only does so while it adds a route, so code that computes names for its spec at any
+    /// other time, including `schema_ref`s, needs to run inside `with_qualified_names(true, ..)`
+    /// to refer to the right components.
+    fn schema_name() -> Option<String> {
         None
     }
 
+    /// The part of a generic component name contributed by this type as a type parameter, e.g.
+    /// the `User` in `Page_User`.
+    ///
+    /// Types with a component name use it. Anything else is named after the Rust type, with module
+    /// paths stripped, so `Vec<my_crate::User>` becomes `Vec_User`.
+    fn type_name_part() -> String {
+        Self::schema_name().unwrap_or_else(short_type_name::<Self>)
+    }
+
+    /// The Rust type whose schema this type has, which owns its component name. Wrappers that
+    /// serialize as the type they wrap, like `Box<T>`, give the owner of `T`, so they don't
+    /// collide with it. See [`SchemaRegistry::claim`].
+    fn schema_owner() -> String {
+        std::any::type_name::<Self>().to_string()
+    }
+
     fn schema_ref() -> Option<ReferenceOr<Schema>> {
         None
     }
@@ -38,6 +66,35 @@ pub trait OaSchema {
     fn parameters() -> Option<Vec<ReferenceOr<oa::Parameter>>> {
         None
     }
+
+    /// The parameters a path extractor like `Path<Self>` reads from the route, in order.
+    ///
+    /// Structs give one parameter per field, named after it. Other types, like tuples, give
+    /// parameters without a name, which take the names of the route's placeholders when the route
+    /// is added. See [`bind_path_parameters`](crate::bind_path_parameters).
+    fn path_parameters() -> Vec<oa::Parameter> {
+        if let Some(Schema { schema_kind: SchemaKind::Type(Type::Object(o)), .. }) = Self::schema() {
+            return o.properties.into_iter()
+                .map(|(name, schema)| oa::Parameter::path(name, schema))
+                .collect();
+        }
+        Self::schema_ref()
+            .map(|schema| oa::Parameter::path("", schema))
+            .into_iter()
+            .collect()
+    }
+
+    /// Add the named component schemas this type relies on to the registry, including its own.
+    ///
+    /// By default, a type with a `schema_name` registers its `schema` under that name. Types whose
+    /// schema refers to other components need to register those too.
+    fn register(registry: &mut SchemaRegistry) {
+        if let Some(name) = Self::schema_name() {
+            if let (true, Some(schema)) = (registry.claim_as(&name, &Self::schema_owner()), Self::schema()) {
+                registry.insert(&name, schema);
+            }
+        }
+    }
 }
 
 #[macro_export]
@@ -55,14 +112,24 @@ macro_rules! impl_oa_schema {
     };
 }
 
+/// Implements `OaSchema` for a wrapper around `T` that serializes as `T`. Wrappers of unsized
+/// types take `?Sized`, followed by any other bounds `T` needs, e.g. `?Sized + ToOwned`.
 #[macro_export]
 macro_rules! impl_oa_schema_passthrough {
-    ($t:ty) => {
-        impl<T> $crate::OaSchema for $t where T: $crate::OaSchema {
-            fn schema_name() -> Option<&'static str> {
+    ($t:ty $(, ?Sized $(+ $bound:path)*)?) => {
+        impl<T> $crate::OaSchema for $t where T: $crate::OaSchema $(+ ?Sized $(+ $bound)*)? {
+            fn schema_name() -> Option<String> {
                 T::schema_name()
             }
 
+            fn type_name_part() -> String {
+                T::type_name_part()
+            }
+
+            fn schema_owner() -> String {
+                T::schema_owner()
+            }
+
             fn schema_ref() -> Option<$crate::ReferenceOr<$crate::Schema>> {
                 T::schema_ref()
             }
@@ -70,6 +137,10 @@ macro_rules! impl_oa_schema_passthrough {
             fn schema() -> Option<$crate::Schema> {
                 T::schema()
             }
+
+            fn register(registry: &mut $crate::SchemaRegistry) {
+                T::register(registry)
+            }
         }
     };
 }
@@ -86,17 +157,69 @@ impl_oa_schema_none!(());
 
 impl_oa_schema!(bool, Schema::new_bool());
 
-impl_oa_schema!(usize, Schema::new_integer());
-impl_oa_schema!(u32, Schema::new_integer());
-impl_oa_schema!(i32, Schema::new_integer());
-impl_oa_schema!(u64, Schema::new_integer());
-impl_oa_schema!(i64, Schema::new_integer());
-impl_oa_schema!(u16, Schema::new_integer());
-impl_oa_schema!(i16, Schema::new_integer());
-impl_oa_schema!(f32, Schema::new_number());
-impl_oa_schema!(f64, Schema::new_number());
+impl_oa_schema!(u8, integer_schema(Some(IntegerFormat::Int32), Some(0), Some(u8::MAX.into())));
+impl_oa_schema!(i8, integer_schema(Some(IntegerFormat::Int32), Some(i8::MIN.into()), Some(i8::MAX.into())));
+impl_oa_schema!(u16, integer_schema(Some(IntegerFormat::Int32), Some(0), Some(u16::MAX.into())));
+impl_oa_schema!(i16, integer_schema(Some(IntegerFormat::Int32), Some(i16::MIN.into()), Some(i16::MAX.into())));
+// `u32` doesn't fit in `int32`, so it is an `int64` with the bounds of a `u32`.
+impl_oa_schema!(u32, integer_schema(Some(IntegerFormat::Int64), Some(0), Some(u32::MAX.into())));
+impl_oa_schema!(i32, integer_schema(Some(IntegerFormat::Int32), None, None));
+impl_oa_schema!(u64, integer_schema(Some(IntegerFormat::Int64), Some(0), None));
+impl_oa_schema!(i64, integer_schema(Some(IntegerFormat::Int64), None, None));
+impl_oa_schema!(usize, integer_schema(Some(IntegerFormat::Int64), Some(0), None));
+impl_oa_schema!(isize, integer_schema(Some(IntegerFormat::Int64), None, None));
+// OpenAPI has no format for 128-bit integers.
+impl_oa_schema!(u128, integer_schema(None, Some(0), None));
+impl_oa_schema!(i128, integer_schema(None, None, None));
+impl_oa_schema!(f32, number_schema(NumberFormat::Float));
+impl_oa_schema!(f64, number_schema(NumberFormat::Double));
+
+/// Non-zero integers share the schema of the underlying integer. Unsigned ones start at 1 instead
+/// of 0. Signed ones can't exclude 0 from their range, so they keep its bounds.
+macro_rules! impl_oa_schema_nonzero {
+    ($t:ty, $int:ty) => {
+        impl_oa_schema!($t, {
+            let mut schema = <$int as OaSchema>::schema().unwrap();
+            if let SchemaKind::Type(Type::Integer(i)) = &mut schema.schema_kind {
+                if i.minimum == Some(0) {
+                    i.minimum = Some(1);
+                }
+            }
+            schema
+        });
+    };
+}
+
+impl_oa_schema_nonzero!(std::num::NonZeroU8, u8);
+impl_oa_schema_nonzero!(std::num::NonZeroI8, i8);
+impl_oa_schema_nonzero!(std::num::NonZeroU16, u16);
+impl_oa_schema_nonzero!(std::num::NonZeroI16, i16);
+impl_oa_schema_nonzero!(std::num::NonZeroU32, u32);
+impl_oa_schema_nonzero!(std::num::NonZeroI32, i32);
+impl_oa_schema_nonzero!(std::num::NonZeroU64, u64);
+impl_oa_schema_nonzero!(std::num::NonZeroI64, i64);
+impl_oa_schema_nonzero!(std::num::NonZeroUsize, usize);
+impl_oa_schema_nonzero!(std::num::NonZeroIsize, isize);
+impl_oa_schema_nonzero!(std::num::NonZeroU128, u128);
+impl_oa_schema_nonzero!(std::num::NonZeroI128, i128);
 
 impl_oa_schema!(String, Schema::new_string());
+impl_oa_schema!(str, Schema::new_string());
+impl_oa_schema!(char, Schema {
+    schema_data: SchemaData::default(),
+    schema_kind: SchemaKind::Type(Type::String(StringType {
+        min_length: Some(1),
+        max_length: Some(1),
+        ..StringType::default()
+    })),
+});
+
+// Smart pointers and references serialize as the value they point to.
+impl_oa_schema_passthrough!(Box<T>, ?Sized);
+impl_oa_schema_passthrough!(std::rc::Rc<T>, ?Sized);
+impl_oa_schema_passthrough!(std::sync::Arc<T>, ?Sized);
+impl_oa_schema_passthrough!(&T, ?Sized);
+impl_oa_schema_passthrough!(std::borrow::Cow<'_, T>, ?Sized + ToOwned);
 
 impl<T> OaSchema for Vec<T>
     where
@@ -125,6 +248,10 @@ impl<T> OaSchema for Vec<T>
             })
         }
     }
+
+    fn register(registry: &mut SchemaRegistry) {
+        T::register(registry)
+    }
 }
 
 
@@ -132,12 +259,27 @@ impl<T> OaSchema for Option<T>
     where
         T: OaSchema,
 {
-    fn schema_name() -> Option<&'static str> {
+    fn schema_name() -> Option<String> {
         T::schema_name()
     }
 
+    /// The schema is nullable, unlike `T`'s, so it gets a name of its own, e.g. `Page_Option_User`.
+    fn type_name_part() -> String {
+        format!("Option_{}", T::type_name_part())
+    }
+
+    fn schema_owner() -> String {
+        generic_owner(std::any::type_name::<Self>(), &[T::schema_owner()])
+    }
+
+    /// A reference can't be nullable itself, so a reference to a component is wrapped, giving
+    /// `allOf: [$ref]` with `nullable: true`.
     fn schema_ref() -> Option<ReferenceOr<Schema>> {
-        T::schema_ref()
+        T::schema_ref().map(|r| {
+            let mut schema = into_schema(r);
+            schema.schema_data.nullable = true;
+            ReferenceOr::Item(schema)
+        })
     }
 
     fn schema() -> Option<Schema> {
@@ -146,16 +288,28 @@ impl<T> OaSchema for Option<T>
             schema
         })
     }
+
+    fn register(registry: &mut SchemaRegistry) {
+        T::register(registry)
+    }
 }
 
 impl<T, E> OaSchema for Result<T, E>
     where
         T: OaSchema,
 {
-    fn schema_name() -> Option<&'static str> {
+    fn schema_name() -> Option<String> {
         T::schema_name()
     }
 
+    fn type_name_part() -> String {
+        T::type_name_part()
+    }
+
+    fn schema_owner() -> String {
+        T::schema_owner()
+    }
+
     fn schema_ref() -> Option<ReferenceOr<Schema>> {
         T::schema_ref()
     }
@@ -163,9 +317,323 @@ impl<T, E> OaSchema for Result<T, E>
     fn schema() -> Option<Schema> {
         T::schema()
     }
+
+    fn register(registry: &mut SchemaRegistry) {
+        T::register(registry)
+    }
+}
+
+/// Schema for a JSON array of `T`, which is how serde serializes sequences and sets.
+fn array_schema<T: OaSchema + ?Sized>(unique_items: bool, len: Option<usize>) -> Schema {
+    Schema {
+        schema_data: SchemaData::default(),
+        schema_kind: SchemaKind::Type(Type::Array(ArrayType {
+            items: T::schema_ref().map(|r| r.boxed()),
+            min_items: len,
+            max_items: len,
+            unique_items,
+        })),
+    }
+}
+
+/// Sequences and sets become arrays. Sets also mark their items as unique.
+macro_rules! impl_oa_schema_array {
+    ($t:ty, unique_items = $unique:literal $(, $param:ident)*) => {
+        impl<T $(, $param)*> OaSchema for $t where T: OaSchema {
+            fn schema_ref() -> Option<ReferenceOr<Schema>> {
+                Self::schema().map(ReferenceOr::Item)
+            }
+
+            fn schema() -> Option<Schema> {
+                Some(array_schema::<T>($unique, None))
+            }
+
+            fn register(registry: &mut SchemaRegistry) {
+                T::register(registry)
+            }
+        }
+    };
+}
+
+impl_oa_schema_array!(std::collections::VecDeque<T>, unique_items = false);
+impl_oa_schema_array!(std::collections::LinkedList<T>, unique_items = false);
+impl_oa_schema_array!([T], unique_items = false);
+impl_oa_schema_array!(std::collections::HashSet<T, S>, unique_items = true, S);
+impl_oa_schema_array!(std::collections::BTreeSet<T>, unique_items = true);
+impl_oa_schema_array!(indexmap::IndexSet<T, S>, unique_items = true, S);
+
+impl<T, const N: usize> OaSchema for [T; N]
+    where
+        T: OaSchema,
+{
+    fn schema_ref() -> Option<ReferenceOr<Schema>> {
+        Self::schema().map(ReferenceOr::Item)
+    }
+
+    fn schema() -> Option<Schema> {
+        Some(array_schema::<T>(false, Some(N)))
+    }
+
+    fn register(registry: &mut SchemaRegistry) {
+        T::register(registry)
+    }
+}
+
+/// Maps become objects whose values are described by `additionalProperties`. Serde writes their
+/// keys as strings, whatever the key type.
+macro_rules! impl_oa_schema_map {
+    ($t:ty $(, $param:ident)*) => {
+        impl<K, V $(, $param)*> OaSchema for $t where V: OaSchema {
+            fn schema_ref() -> Option<ReferenceOr<Schema>> {
+                Self::schema().map(ReferenceOr::Item)
+            }
+
+            fn schema() -> Option<Schema> {
+                Some(Schema {
+                    schema_data: SchemaData::default(),
+                    schema_kind: SchemaKind::Type(Type::Object(ObjectType {
+                        additional_properties: V::schema_ref().map(|r| AdditionalProperties::Schema(Box::new(r))),
+                        ..ObjectType::default()
+                    })),
+                })
+            }
+
+            fn register(registry: &mut SchemaRegistry) {
+                V::register(registry)
+            }
+        }
+    };
 }
 
+impl_oa_schema_map!(std::collections::HashMap<K, V, S>, S);
+impl_oa_schema_map!(std::collections::BTreeMap<K, V>);
+impl_oa_schema_map!(indexmap::IndexMap<K, V, S>, S);
+
+/// Tuples become fixed-length arrays. See [`tuple_schema`].
+macro_rules! impl_oa_schema_tuple {
+    ($($t:ident),+) => {
+        impl<$($t),+> OaSchema for ($($t,)+) where $($t: OaSchema),+ {
+            fn schema_ref() -> Option<ReferenceOr<Schema>> {
+                Self::schema().map(ReferenceOr::Item)
+            }
+
+            fn schema() -> Option<Schema> {
+                Some(tuple_schema(vec![$($t::schema_ref()?),+]))
+            }
+
+            fn path_parameters() -> Vec<oa::Parameter> {
+                vec![$(oa::Parameter::path("", $t::schema_ref().expect("No schema found for path parameter"))),+]
+            }
+
+            fn register(registry: &mut SchemaRegistry) {
+                $($t::register(registry);)+
+            }
+        }
+    };
+}
+
+impl_oa_schema_tuple!(A);
+impl_oa_schema_tuple!(A, B);
+impl_oa_schema_tuple!(A, B, C);
+impl_oa_schema_tuple!(A, B, C, D);
+impl_oa_schema_tuple!(A, B, C, D, E);
+impl_oa_schema_tuple!(A, B, C, D, E, F);
+impl_oa_schema_tuple!(A, B, C, D, E, F, G);
+impl_oa_schema_tuple!(A, B, C, D, E, F, G, H);
+
 #[cfg(feature = "uuid")]
 impl_oa_schema!(uuid::Uuid, Schema::new_string().with_format("uuid"));
 
-impl_oa_schema!(serde_json::Value, Schema::new_object());
\ No newline at end of file
+/// A `serde_json::Value` can be any JSON value, including `null`.
+fn any_schema() -> Schema {
+    let mut schema = Schema::new_any();
+    schema.schema_data.nullable = true;
+    schema
+}
+
+impl_oa_schema!(serde_json::Value, any_schema());
+impl_oa_schema_map!(serde_json::Map<K, V>);
+#[cfg(feature = "raw_value")]
+impl_oa_schema!(serde_json::value::RawValue, any_schema());
+
+thread_local! {
+    static QUALIFY_COMPONENT_NAMES: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `f` with the component names of derived types qualified by their module path, so
+/// `billing::Invoice` becomes `billing.Invoice`. Servers that qualify names add their routes this
+/// way. Only names computed by `f`, on this thread, are affected; outside of it, names are not
+/// qualified. See [`OaSchema::schema_name`].
+pub fn with_qualified_names<R>(qualify: bool, f: impl FnOnce() -> R) -> R {
+    /// Restores the previous setting, even if `f` panics.
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            QUALIFY_COMPONENT_NAMES.with(|q| q.set(self.0));
+        }
+    }
+
+    let _restore = Restore(QUALIFY_COMPONENT_NAMES.with(|q| q.replace(qualify)));
+    f()
+}
+
+/// The component name of a derived type, given its name and the `module_path!()` it's defined in.
+/// The crate name is left out of qualified names.
+pub fn component_name(name: &str, module_path: &str) -> String {
+    if !QUALIFY_COMPONENT_NAMES.with(Cell::get) {
+        return name.to_string();
+    }
+    module_path.split("::")
+        .skip(1)
+        .chain(std::iter::once(name))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// The component name of a type from another crate. It is qualified like the names of derived
+/// types, using the module the type is defined in.
+pub fn remote_name<T: ?Sized>() -> String {
+    let path = std::any::type_name::<T>();
+    let path = path.split('<').next().unwrap_or(path);
+    let module = path.rsplit_once("::").map_or("", |(module, _)| module);
+    component_name(&short_type_name::<T>(), module)
+}
+
+/// The owner of a generic derived type, given the owner of its base type, e.g. `my_crate::Page`,
+/// and the owners of its type arguments. See [`OaSchema::schema_owner`].
+pub fn generic_owner(base: &str, args: &[String]) -> String {
+    let base = base.split('<').next().unwrap_or(base);
+    format!("{}<{}>", base, args.join(", "))
+}
+
+/// The name of a Rust type with module paths stripped, so `Vec<my_crate::User>` becomes `Vec_User`.
+fn short_type_name<T: ?Sized>() -> String {
+    std::any::type_name::<T>()
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+        .filter_map(|path| path.rsplit("::").next())
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Allow or forbid keys beyond the declared properties of an object schema. Other schemas are left
+/// as they are.
+pub fn set_additional_properties(schema: &mut Schema, allowed: bool) {
+    if let SchemaKind::Type(Type::Object(o)) = &mut schema.schema_kind {
+        o.additional_properties = Some(AdditionalProperties::Any(allowed));
+    }
+}
+
+/// Schema for an integer type. The bounds are only needed where the format doesn't imply them,
+/// like for unsigned or narrow types.
+fn integer_schema(format: Option<IntegerFormat>, minimum: Option<i64>, maximum: Option<i64>) -> Schema {
+    Schema {
+        schema_data: SchemaData::default(),
+        schema_kind: SchemaKind::Type(Type::Integer(IntegerType {
+            format: format.map(VariantOrUnknownOrEmpty::Item).unwrap_or_default(),
+            minimum,
+            maximum,
+            ..IntegerType::default()
+        })),
+    }
+}
+
+fn number_schema(format: NumberFormat) -> Schema {
+    Schema {
+        schema_data: SchemaData::default(),
+        schema_kind: SchemaKind::Type(Type::Number(NumberType {
+            format: VariantOrUnknownOrEmpty::Item(format),
+            ..NumberType::default()
+        })),
+    }
+}
+
+/// Turn a schema reference into a schema that can carry its own schema data, like a description.
+///
+/// OpenAPI 3.0 ignores everything next to a `$ref`, so references are wrapped in a single-item
+/// `allOf`. Inline schemas are returned as is.
+pub fn into_schema(schema: ReferenceOr<Schema>) -> Schema {
+    match schema {
+        ReferenceOr::Item(schema) => schema,
+        reference => Schema {
+            schema_data: SchemaData::default(),
+            schema_kind: SchemaKind::AllOf { all_of: vec![reference] },
+        },
+    }
+}
+
+/// Schema for a fixed-length JSON array, which is how serde serializes tuples.
+///
+/// OpenAPI 3.0 can't type each position separately, so `items` is the element schema when they
+/// are all the same, and a `oneOf` of the element schemas otherwise.
+pub fn tuple_schema(items: Vec<ReferenceOr<Schema>>) -> Schema {
+    let len = items.len();
+    let mut unique: Vec<ReferenceOr<Schema>> = Vec::new();
+    for item in items {
+        if !unique.contains(&item) {
+            unique.push(item);
+        }
+    }
+    let items = if unique.len() == 1 {
+        unique.pop().unwrap()
+    } else {
+        ReferenceOr::Item(Schema {
+            schema_data: SchemaData::default(),
+            schema_kind: SchemaKind::OneOf { one_of: unique },
+        })
+    };
+    Schema {
+        schema_data: SchemaData::default(),
+        schema_kind: SchemaKind::Type(Type::Array(ArrayType {
+            items: Some(items.boxed()),
+            min_items: Some(len),
+            max_items: Some(len),
+            unique_items: false,
+        })),
+    }
+}
+
+/// Merge the schema of a `#[serde(flatten)]` field into the schema of its parent.
+///
+/// Objects are inlined: their properties and required fields join the parent's, and a map's value
+/// schema becomes the parent's `additionalProperties`. A flattened `Option` contributes no
+/// required fields. Anything else, such as an enum, is combined with the parent using `allOf`.
+pub fn flatten_schema(mut parent: Schema, flattened: Schema) -> Schema {
+    let nullable = flattened.schema_data.nullable;
+    let target = match &mut parent.schema_kind {
+        SchemaKind::Type(Type::Object(o)) => Some(o),
+        SchemaKind::AllOf { all_of } => match all_of.first_mut() {
+            Some(ReferenceOr::Item(Schema { schema_kind: SchemaKind::Type(Type::Object(o)), .. })) => Some(o),
+            _ => None,
+        },
+        _ => None,
+    };
+    match (target, flattened.schema_kind) {
+        (Some(target), SchemaKind::Type(Type::Object(o))) => {
+            target.properties.extend(o.properties);
+            if !nullable {
+                target.required.extend(o.required);
+            }
+            // A flattened map collects the unknown keys of the parent. Other settings, like
+            // `additionalProperties: false`, only made sense for the flattened object itself.
+            if let Some(AdditionalProperties::Schema(_)) = o.additional_properties {
+                target.additional_properties = o.additional_properties;
+            }
+            parent
+        }
+        (_, schema_kind) => {
+            let flattened = Schema { schema_data: flattened.schema_data, schema_kind };
+            if let SchemaKind::AllOf { all_of } = &mut parent.schema_kind {
+                all_of.push(ReferenceOr::Item(flattened));
+                return parent;
+            }
+            Schema {
+                schema_data: std::mem::take(&mut parent.schema_data),
+                schema_kind: SchemaKind::AllOf {
+                    all_of: vec![ReferenceOr::Item(parent), ReferenceOr::Item(flattened)],
+                },
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{impl_oa_schema_passthrough, OaSchema};
+use openapiv3 as oa;
+use openapiv3::ReferenceOr;
+use crate::{impl_oa_schema_passthrough, query_parameters, OaSchema, SchemaRegistry};
 
 impl_oa_schema_passthrough!(axum::Json<T>);
 
@@ -14,10 +16,26 @@ impl<T> OaSchema for axum::extract::ConnectInfo<T> {}
 
 impl OaSchema for axum::http::HeaderMap {}
 
-// TODO fill this out
-impl<T> OaSchema for axum::extract::Query<T> {}
-
-// TODO fill this out
-impl<T> OaSchema for axum::extract::Path<T> {}
+impl<T: OaSchema> OaSchema for axum::extract::Query<T> {
+    fn parameters() -> Option<Vec<ReferenceOr<oa::Parameter>>> {
+        Some(query_parameters::<T>())
+    }
+
+    fn register(registry: &mut SchemaRegistry) {
+        T::register(registry)
+    }
+}
+
+/// Path parameters are named after the route's `:name` segments when the route is added. See
+/// [`OaSchema::path_parameters`].
+impl<T: OaSchema> OaSchema for axum::extract::Path<T> {
+    fn parameters() -> Option<Vec<ReferenceOr<oa::Parameter>>> {
+        Some(T::path_parameters().into_iter().map(ReferenceOr::Item).collect())
+    }
+
+    fn register(registry: &mut SchemaRegistry) {
+        T::register(registry)
+    }
+}
 
 impl OaSchema for axum::http::request::Parts {}
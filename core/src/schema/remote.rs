@@ -0,0 +1,24 @@
+use std::marker::PhantomData;
+use openapiv3::{ReferenceOr, Schema};
+use crate::{remote_name, OaSchema, SchemaRegistry};
+
+/// Refers to a type from another crate that doesn't implement `OaSchema`, for use in
+/// `#[openapi(as = oasgen::core::Remote<other_crate::Money>)]`.
+///
+/// The type becomes a component named after it, e.g. `Money`, whose schema is given to the server
+/// with `Server::override_schema::<other_crate::Money>(..)`.
+pub struct Remote<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized> OaSchema for Remote<T> {
+    fn schema_name() -> Option<String> {
+        Some(remote_name::<T>())
+    }
+
+    fn schema_ref() -> Option<ReferenceOr<Schema>> {
+        Some(ReferenceOr::schema_ref(&remote_name::<T>()))
+    }
+
+    fn register(registry: &mut SchemaRegistry) {
+        registry.claim::<T>(&remote_name::<T>());
+    }
+}
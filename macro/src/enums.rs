@@ -0,0 +1,304 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{DataEnum, Fields, Variant};
+use oasgen_core::{OpenApiAttributes, RenameRule, SerdeAttributes};
+use crate::{util, DerivedSchema};
+use crate::fields::{object_properties, register_fields, tuple_schema, FieldInfo};
+
+/// A variant that appears in the schema, along with its name on the wire.
+struct VariantInfo<'a> {
+    variant: &'a Variant,
+    name: String,
+    rename_fields: Option<RenameRule>,
+    /// Statements that set the variant's title, description, example, and deprecation on `schema`.
+    describe: TokenStream2,
+}
+
+impl VariantInfo<'_> {
+    /// Wrap the expression for a variant's schema, so it carries the variant's description.
+    fn described(&self, schema: TokenStream2) -> TokenStream2 {
+        let describe = &self.describe;
+        quote! {{
+            #[allow(unused_mut)]
+            let mut schema = #schema;
+            #describe
+            schema
+        }}
+    }
+
+    /// Like [`Self::described`], for an expression of a `ReferenceOr<Schema>`.
+    fn described_ref(&self, schema: TokenStream2) -> TokenStream2 {
+        if self.describe.is_empty() {
+            return schema;
+        }
+        let schema = self.described(quote! { ::oasgen::core::into_schema(#schema) });
+        quote! { ::oasgen::ReferenceOr::Item(#schema) }
+    }
+}
+
+/// How serde represents the enum on the wire. See <https://serde.rs/enum-representations.html>
+enum Tagging<'a> {
+    External,
+    Internal { tag: &'a str },
+    Adjacent { tag: &'a str, content: &'a str },
+    Untagged,
+}
+
+impl<'a> Tagging<'a> {
+    fn new(serde: &'a SerdeAttributes) -> Self {
+        match (&serde.tag, &serde.content, serde.untagged) {
+            (_, _, true) => Tagging::Untagged,
+            (Some(tag), Some(content), _) => Tagging::Adjacent { tag, content },
+            (Some(tag), None, _) => Tagging::Internal { tag },
+            (None, _, false) => Tagging::External,
+        }
+    }
+}
+
+/// Derive the schema of an enum.
+///
+/// Enums made up only of unit variants serialize as plain strings, so they become a string
+/// schema with an `enum` list of the variant names. Otherwise, the enum becomes a `oneOf` of
+/// its variants, following the serde tagging mode. Internally and adjacently tagged variants are
+/// registered as their own components, so the `discriminator` can map tag values to them.
+pub fn derive_enum(data: &DataEnum, serde: &SerdeAttributes) -> syn::Result<DerivedSchema> {
+    let mut variants = Vec::new();
+    for variant in &data.variants {
+        let attr = OpenApiAttributes::try_from(&variant.attrs)?;
+        let variant_serde = SerdeAttributes::try_from(&variant.attrs)?;
+        if attr.skip || variant_serde.is_skipped() {
+            continue;
+        }
+        let name = variant_name(&variant.ident, &variant_serde, serde);
+        // Fields of struct variants are renamed by the variant's `rename_all`, falling back to
+        // the enum's `rename_all_fields`.
+        let rename_fields = variant_serde.rename_all.or(serde.rename_all_fields);
+        let mut describe = util::describe(&variant.attrs, attr.title.as_ref(), attr.description.as_ref());
+        describe.extend(util::example(attr.example.as_ref(), attr.example_fn.as_ref())?);
+        describe.extend(util::deprecated(&variant.attrs, attr.deprecated));
+        variants.push(VariantInfo { variant, name, rename_fields, describe });
+    }
+
+    let tagging = Tagging::new(serde);
+    let mut derived = match tagging {
+        Tagging::External => external_enum(&variants)?,
+        Tagging::Untagged => {
+            let mut schemas = Vec::new();
+            for variant in &variants {
+                let schema = content_schema(variant)?.unwrap_or_else(|| quote! {
+                    ::oasgen::ReferenceOr::Item(::oasgen::Schema {
+                        schema_data: ::oasgen::SchemaData {
+                            nullable: true,
+                            ..Default::default()
+                        },
+                        schema_kind: ::oasgen::SchemaKind::Any(::oasgen::AnySchema {
+                            enumeration: vec![Default::default()],
+                            ..Default::default()
+                        }),
+                    })
+                });
+                schemas.push(variant.described_ref(schema));
+            }
+            DerivedSchema {
+                schema: one_of(quote! { Default::default() }, schemas.into_iter()),
+                register: quote! {},
+            }
+        }
+        Tagging::Internal { tag } | Tagging::Adjacent { tag, .. } => {
+            let mut mapping = Vec::new();
+            let mut refs = Vec::new();
+            let mut register = Vec::new();
+            for info in &variants {
+                let VariantInfo { variant, name, rename_fields, .. } = info;
+                let variant_ident = variant.ident.to_string();
+                let component = quote! {
+                    format!("{}_{}", <Self as ::oasgen::OaSchema>::schema_name().unwrap(), #variant_ident)
+                };
+                let tag_property = quote! {
+                    o.add_property(#tag, ::oasgen::Schema::new_str_enum(vec![#name.to_string()])).unwrap();
+                    o.required_mut().unwrap().push(#tag.to_string());
+                };
+                let schema = match (&tagging, &variant.fields) {
+                    (Tagging::Internal { .. }, Fields::Named(fields)) => {
+                        let properties = object_properties(&fields.named, *rename_fields, false)?;
+                        quote! {{
+                            let mut o = ::oasgen::Schema::new_object();
+                            #tag_property
+                            #properties
+                            o
+                        }}
+                    }
+                    (Tagging::Internal { .. }, Fields::Unnamed(fields)) if fields.unnamed.len() > 1 => {
+                        return Err(syn::Error::new_spanned(variant, "#[serde(tag = \"...\")] cannot be used with tuple variants"));
+                    }
+                    (Tagging::Internal { .. }, fields) => {
+                        let tag_object = quote! {{
+                            let mut o = ::oasgen::Schema::new_object();
+                            #tag_property
+                            o
+                        }};
+                        // The inner type's properties sit next to the tag, as with
+                        // `#[serde(flatten)]`, so `additionalProperties` on either accounts for
+                        // both.
+                        match fields.iter().next() {
+                            Some(field) => {
+                                let content = FieldInfo::new(field)?.inline_schema();
+                                quote! { ::oasgen::core::flatten_schema(#tag_object, #content) }
+                            }
+                            None => tag_object,
+                        }
+                    }
+                    (Tagging::Adjacent { content: content_key, .. }, _) => {
+                        let content = content_schema(info)?.map(|content| quote! {
+                            o.add_property(#content_key, #content).unwrap();
+                            o.required_mut().unwrap().push(#content_key.to_string());
+                        });
+                        quote! {{
+                            let mut o = ::oasgen::Schema::new_object();
+                            #tag_property
+                            #content
+                            o
+                        }}
+                    }
+                    _ => unreachable!(),
+                };
+                let schema = info.described(schema);
+                mapping.push(quote! { (#name.to_string(), format!("#/components/schemas/{}", #component)) });
+                refs.push(quote! { ::oasgen::ReferenceOr::schema_ref(&#component) });
+                // The component is owned by the variant, so a type with the same name collides.
+                register.push(quote! {
+                    let owner = format!("{}::{}", <Self as ::oasgen::OaSchema>::schema_owner(), #variant_ident);
+                    if registry.claim_as(&#component, &owner) {
+                        registry.insert(&#component, #schema);
+                    }
+                });
+            }
+            let schema_data = quote! {
+                ::oasgen::SchemaData {
+                    discriminator: Some(::oasgen::Discriminator {
+                        property_name: #tag.to_string(),
+                        mapping: vec![#(#mapping),*].into_iter().collect(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }
+            };
+            DerivedSchema {
+                schema: one_of(schema_data, refs.into_iter()),
+                register: quote! { #(#register)* },
+            }
+        }
+    };
+    let register_fields = match tagging {
+        // Newtype variants are inlined like flattened fields.
+        Tagging::Internal { .. } => {
+            let mut register = Vec::new();
+            for info in &variants {
+                match &info.variant.fields {
+                    Fields::Unnamed(fields) => {
+                        for field in fields.unnamed.iter() {
+                            register.extend(FieldInfo::new(field)?.register_inlined());
+                        }
+                    }
+                    fields => register.push(register_fields(fields)?),
+                }
+            }
+            quote! { #(#register)* }
+        }
+        _ => register_fields(variants.iter().flat_map(|v| &v.variant.fields))?,
+    };
+    let register = derived.register;
+    derived.register = quote! { #register #register_fields };
+    Ok(derived)
+}
+
+/// Externally tagged enums serialize unit variants as strings, and other variants as an object
+/// with a single key, the variant name.
+fn external_enum(variants: &[VariantInfo]) -> syn::Result<DerivedSchema> {
+    let units = variants.iter()
+        .filter(|v| matches!(v.variant.fields, Fields::Unit))
+        .map(|v| &v.name)
+        .collect::<Vec<_>>();
+    let unit_schema = quote! {
+        ::oasgen::Schema::new_str_enum(vec![#(#units.to_string()),*])
+    };
+    if units.len() == variants.len() {
+        return Ok(DerivedSchema {
+            schema: quote! { Some(#unit_schema) },
+            register: quote! {},
+        });
+    }
+
+    let mut schemas = Vec::new();
+    if !units.is_empty() {
+        schemas.push(quote! { ::oasgen::ReferenceOr::Item(#unit_schema) });
+    }
+    for variant in variants {
+        let Some(content) = content_schema(variant)? else {
+            continue;
+        };
+        let name = &variant.name;
+        let schema = variant.described(quote! {{
+            let mut o = ::oasgen::Schema::new_object();
+            o.add_property(#name, #content).unwrap();
+            o.required_mut().unwrap().push(#name.to_string());
+            o
+        }});
+        schemas.push(quote! {
+            ::oasgen::ReferenceOr::Item(#schema)
+        });
+    }
+    Ok(DerivedSchema {
+        schema: one_of(quote! { Default::default() }, schemas.into_iter()),
+        register: quote! {},
+    })
+}
+
+fn one_of(schema_data: TokenStream2, schemas: impl Iterator<Item=TokenStream2>) -> TokenStream2 {
+    quote! {
+        Some(::oasgen::Schema {
+            schema_data: #schema_data,
+            schema_kind: ::oasgen::SchemaKind::OneOf {
+                one_of: vec![#(#schemas),*],
+            },
+        })
+    }
+}
+
+/// Expression for the `ReferenceOr<Schema>` of the data a variant carries, or `None` for unit
+/// variants.
+fn content_schema(info: &VariantInfo) -> syn::Result<Option<TokenStream2>> {
+    let variant = info.variant;
+    Ok(match &variant.fields {
+        Fields::Unit => None,
+        Fields::Named(fields) => {
+            let properties = object_properties(&fields.named, info.rename_fields, false)?;
+            Some(quote! {
+                ::oasgen::ReferenceOr::Item({
+                    let mut o = ::oasgen::Schema::new_object();
+                    #properties
+                    o
+                })
+            })
+        }
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            Some(FieldInfo::new(&fields.unnamed[0])?.schema(&variant.ident.to_string()))
+        }
+        Fields::Unnamed(fields) => {
+            let schema = tuple_schema(&fields.unnamed)?;
+            Some(quote! { ::oasgen::ReferenceOr::Item(#schema) })
+        }
+    })
+}
+
+/// The name serde uses for a variant on the wire.
+pub fn variant_name(ident: &syn::Ident, variant: &SerdeAttributes, container: &SerdeAttributes) -> String {
+    if let Some(rename) = &variant.rename {
+        return rename.clone();
+    }
+    let name = ident.to_string();
+    match container.rename_all {
+        Some(rule) => rule.apply_to_variant(&name),
+        None => name,
+    }
+}
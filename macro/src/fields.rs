@@ -0,0 +1,260 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{Field, LitStr};
+use oasgen_core::{OpenApiAttributes, RenameRule, SerdeAttributes};
+use crate::util;
+
+/// A field of a struct or enum variant, along with its parsed attributes.
+pub struct FieldInfo<'a> {
+    pub field: &'a Field,
+    pub attr: OpenApiAttributes,
+    pub serde: SerdeAttributes,
+    /// The function from `#[openapi(schema_with = "..")]`.
+    schema_with: Option<syn::Path>,
+    /// Statement that sets the example on `schema`.
+    example: Option<TokenStream2>,
+}
+
+impl<'a> FieldInfo<'a> {
+    pub fn new(field: &'a Field) -> syn::Result<Self> {
+        #[allow(unused_mut)]
+        let mut attr = OpenApiAttributes::try_from(&field.attrs)?;
+        #[cfg(feature = "validator")]
+        attr.add_validate_attributes(&field.attrs)?;
+        let schema_with = attr.schema_with.as_ref().map(LitStr::parse).transpose()?;
+        let example = util::example(attr.example.as_ref(), attr.example_fn.as_ref())?;
+        Ok(Self {
+            field,
+            attr,
+            serde: SerdeAttributes::try_from(&field.attrs)?,
+            schema_with,
+            example,
+        })
+    }
+
+    /// The type whose schema documents the field, which `#[openapi(as = ..)]` overrides.
+    pub fn ty(&self) -> &syn::Type {
+        self.attr.r#as.as_ref().unwrap_or(&self.field.ty)
+    }
+
+    /// Expression for the inline `Schema` of the field, used to merge flattened fields.
+    pub fn inline_schema(&self) -> TokenStream2 {
+        if let Some(schema_with) = &self.schema_with {
+            return quote! { #schema_with() };
+        }
+        let ty = self.ty();
+        quote! {
+            <#ty as ::oasgen::OaSchema>::schema().expect("No schema found for flattened field")
+        }
+    }
+
+    /// Statement that registers the components the field's schema relies on. A flattened field's
+    /// schema is inlined, so its own component is left out.
+    pub fn register(&self) -> Option<TokenStream2> {
+        if self.schema_with.is_some() {
+            return None;
+        }
+        if self.serde.flatten {
+            return self.register_inlined();
+        }
+        let ty = self.ty();
+        Some(quote! { <#ty as ::oasgen::OaSchema>::register(registry); })
+    }
+
+    /// Like [`Self::register`], for a field whose schema is inlined with [`Self::inline_schema`].
+    pub fn register_inlined(&self) -> Option<TokenStream2> {
+        if self.schema_with.is_some() {
+            return None;
+        }
+        let ty = self.ty();
+        Some(quote! { registry.register_dependencies::<#ty>(); })
+    }
+
+    pub fn is_skipped(&self) -> bool {
+        self.attr.skip || self.serde.is_skipped()
+    }
+
+    /// The name serde uses for the field on the wire.
+    pub fn name(&self, rename_all: Option<RenameRule>) -> String {
+        if let Some(rename) = &self.serde.rename {
+            return rename.clone();
+        }
+        let name = self.field.ident.as_ref().unwrap().unraw().to_string();
+        match rename_all {
+            Some(rule) => rule.apply_to_field(&name),
+            None => name,
+        }
+    }
+
+    /// Whether the field is always present: serde always serializes it, and it can't be left
+    /// out when deserializing. `container_default` is set for `#[serde(default)]` structs.
+    ///
+    /// An `Option<Option<T>>` is never required, since a missing field and `null` mean different
+    /// things.
+    pub fn is_required(&self, container_default: bool) -> bool {
+        !util::is_double_option(&self.field.ty)
+            && !self.serde.skip_serializing
+            && self.serde.skip_serializing_if.is_none()
+            && !self.serde.default
+            && !container_default
+    }
+
+    /// Expression for the `ReferenceOr<Schema>` of the field, with any field-level customizations
+    /// applied. Types with a component are referenced with `$ref`.
+    pub fn schema(&self, name: &str) -> TokenStream2 {
+        let ty = self.ty();
+        let schema_ref = match &self.schema_with {
+            Some(schema_with) => quote! { ::oasgen::ReferenceOr::Item(#schema_with()) },
+            None => quote! {
+                <#ty as ::oasgen::OaSchema>::schema_ref().expect(concat!("No schema found for ", #name))
+            },
+        };
+        let default = self.attr.default.as_ref().map(|default| quote! {
+            schema.schema_data.default = Some(::oasgen::core::serde_json::to_value(#default).unwrap());
+        });
+        let describe = util::describe(&self.field.attrs, self.attr.title.as_ref(), self.attr.description.as_ref());
+        let constraints = self.constraints();
+        let example = &self.example;
+        let flags = self.flags();
+        if default.is_none() && describe.is_empty() && constraints.is_none() && example.is_none() && flags.is_empty() {
+            return schema_ref;
+        }
+        quote! {{
+            let mut schema = ::oasgen::core::into_schema(#schema_ref);
+            #default
+            #describe
+            #example
+            #flags
+            #constraints
+            ::oasgen::ReferenceOr::Item(schema)
+        }}
+    }
+
+    /// Statements that set `readOnly`, `writeOnly` and `deprecated`.
+    ///
+    /// Besides the `#[openapi(..)]` flags, a field serde never serializes only appears in
+    /// requests, so it's `writeOnly`, and a field serde never deserializes is `readOnly`.
+    fn flags(&self) -> TokenStream2 {
+        let read_only = (self.attr.read_only || self.serde.skip_deserializing).then(|| quote! {
+            schema.schema_data.read_only = true;
+        });
+        let write_only = (self.attr.write_only || self.serde.skip_serializing).then(|| quote! {
+            schema.schema_data.write_only = true;
+        });
+        let deprecated = util::deprecated(&self.field.attrs, self.attr.deprecated);
+        quote! { #read_only #write_only #deprecated }
+    }
+
+    /// Statement that applies the validation constraints of `#[openapi(..)]` to `schema`.
+    fn constraints(&self) -> Option<TokenStream2> {
+        let attr = &self.attr;
+        let mut values = Vec::new();
+        let counts = [
+            ("min_length", &attr.min_length),
+            ("max_length", &attr.max_length),
+            ("min_items", &attr.min_items),
+            ("max_items", &attr.max_items),
+        ];
+        for (key, value) in counts {
+            if let Some(value) = value {
+                let key = format_ident!("{}", key);
+                values.push(quote! { #key: Some(#value) });
+            }
+        }
+        let numbers = [
+            ("minimum", &attr.minimum),
+            ("maximum", &attr.maximum),
+            ("exclusive_minimum", &attr.exclusive_minimum),
+            ("exclusive_maximum", &attr.exclusive_maximum),
+            ("multiple_of", &attr.multiple_of),
+        ];
+        for (key, value) in numbers {
+            if let Some(value) = value {
+                let key = format_ident!("{}", key);
+                values.push(quote! { #key: Some((#value) as f64) });
+            }
+        }
+        if let Some(pattern) = &attr.pattern {
+            values.push(quote! { pattern: Some(#pattern.to_string()) });
+        }
+        if let Some(format) = &attr.format {
+            values.push(quote! { format: Some(#format.to_string()) });
+        }
+        if attr.unique_items {
+            values.push(quote! { unique_items: true });
+        }
+        if values.is_empty() {
+            return None;
+        }
+        Some(quote! {
+            ::oasgen::core::Constraints {
+                #(#values,)*
+                ..Default::default()
+            }.apply(&mut schema);
+        })
+    }
+}
+
+/// Statements that add named fields as properties of the object schema bound to `o`, and merge
+/// in `#[serde(flatten)]` fields.
+///
+/// `rename_all` and `default` are the container's `#[serde(rename_all)]` and `#[serde(default)]`.
+pub fn object_properties(fields: &Punctuated<Field, Comma>, rename_all: Option<RenameRule>, default: bool) -> syn::Result<TokenStream2> {
+    let mut properties = Vec::new();
+    let mut flattened = Vec::new();
+    for field in fields {
+        let field = FieldInfo::new(field)?;
+        if field.is_skipped() {
+            continue;
+        }
+        if field.serde.flatten {
+            let schema = field.inline_schema();
+            flattened.push(quote! {
+                o = ::oasgen::core::flatten_schema(o, #schema);
+            });
+            continue;
+        }
+        let name = field.name(rename_all);
+        let required = field.is_required(default).then(|| quote! {
+            o.required_mut().unwrap().push(#name.to_string());
+        });
+        let schema = field.schema(&name);
+        properties.push(quote! {
+            o.add_property(#name, #schema).unwrap();
+            #required
+        });
+    }
+    // Flattened fields come last, since a flattened enum turns `o` into an `allOf`.
+    Ok(quote! { #(#properties)* #(#flattened)* })
+}
+
+/// Expression for the fixed-length array schema that tuple fields serialize as.
+pub fn tuple_schema(fields: &Punctuated<Field, Comma>) -> syn::Result<TokenStream2> {
+    let mut items = Vec::new();
+    for field in fields {
+        let field = FieldInfo::new(field)?;
+        if field.is_skipped() {
+            continue;
+        }
+        items.push(field.schema("tuple field"));
+    }
+    Ok(quote! {
+        ::oasgen::core::tuple_schema(vec![#(#items),*])
+    })
+}
+
+/// Statements that register the component schemas the field types rely on.
+pub fn register_fields<'a>(fields: impl IntoIterator<Item=&'a Field>) -> syn::Result<TokenStream2> {
+    let mut register = Vec::new();
+    for field in fields {
+        let field = FieldInfo::new(field)?;
+        if field.is_skipped() {
+            continue;
+        }
+        register.extend(field.register());
+    }
+    Ok(quote! { #(#register)* })
+}
@@ -1,66 +1,284 @@
 #![allow(non_snake_case)]
 
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, DeriveInput, ReturnType, Token};
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, ReturnType, Token};
 use quote::{quote};
-use oasgen_core::OpenApiAttributes;
+use oasgen_core::{OpenApiContainerAttributes, SerdeAttributes};
+use crate::fields::FieldInfo;
 
 mod util;
+mod enums;
+mod fields;
 
 #[proc_macro_derive(OaSchema, attributes(openapi))]
 pub fn derive_oaschema(item: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(item as DeriveInput);
     let id = &ast.ident;
-    let fields = util::get_fields(&ast);
+    let attr = match OpenApiContainerAttributes::try_from(&ast.attrs) {
+        Ok(attr) => attr,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let serde = match SerdeAttributes::try_from(&ast.attrs) {
+        Ok(serde) => serde,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
-    let fields: Vec<(&syn::Field, OpenApiAttributes)> = fields.into_iter().map(|f| {
-        (f, OpenApiAttributes::try_from(&f.attrs).unwrap())
-    }).collect::<Vec<_>>();
+    let describe = util::describe(&ast.attrs, attr.title.as_ref(), attr.description.as_ref());
+    let deprecated = util::deprecated(&ast.attrs, attr.deprecated);
+    let example = match util::example(attr.example.as_ref(), attr.example_fn.as_ref()) {
+        Ok(example) => example,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
-    let properties = fields.iter().map(|(f, attr)| {
-        if attr.skip {
-            return quote! {};
-        }
-        let name = f.ident.as_ref().unwrap().to_string();
-        let ty = &f.ty;
-        quote! {
-            o.add_property(#name, <#ty as OaSchema>::schema().expect(concat!("No schema found for ", #name))).unwrap();
+    if attr.r#as.is_some() || attr.inline {
+        // Without a component of its own, the type has no name to set, and no object to close.
+        let unsupported = attr.rename.as_ref().map(|rename| syn::Error::new_spanned(
+            rename,
+            "#[openapi(rename)] can't be used with #[openapi(as)] or #[openapi(inline)], which use the other type's schema",
+        )).or_else(|| attr.additional_properties.as_ref().map(|allowed| syn::Error::new_spanned(
+            allowed,
+            "#[openapi(additional_properties)] can't be used with #[openapi(as)] or #[openapi(inline)], which use the other type's schema",
+        )));
+        if let Some(e) = unsupported {
+            return e.to_compile_error().into();
         }
-    });
+        let customize = quote! { #describe #example #deprecated };
+        let ty = match (&attr.r#as, newtype_field(&ast, &serde)) {
+            (Some(ty), _) => ty,
+            (None, Some(field)) => &field.ty,
+            (None, None) => {
+                return syn::Error::new_spanned(id, "#[openapi(inline)] can only be used on newtype structs")
+                    .to_compile_error()
+                    .into();
+            }
+        };
+        return derive_forwarding(&ast, ty, customize).into();
+    }
 
-    let required = fields.iter().map(|(f, attr)| {
-        if attr.skip || attr.skip_serializing_if.is_some() {
-            return quote! {};
-        }
-        let name = f.ident.as_ref().unwrap().to_string();
-        quote! { #name.to_string(), }
-    });
-    let required = quote! { vec! [ #(#required)* ] };
+    let derived = match (&attr.schema_with, &ast.data) {
+        (Some(schema_with), _) => schema_with.parse::<syn::Path>().map(|schema_with| DerivedSchema {
+            schema: quote! { Some(#schema_with()) },
+            register: quote! {},
+        }),
+        (None, Data::Enum(data)) => enums::derive_enum(data, &serde),
+        (None, _) => derive_struct_schema(&ast, &serde, &attr),
+    };
+    let DerivedSchema { schema, register } = match derived {
+        Ok(derived) => derived,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    // A mirror of a type from another crate documents that type, like serde's remote derive.
+    let remote = match (&attr.remote, &serde.remote) {
+        (Some(remote), _) => remote.parse::<syn::Type>().map(Some),
+        (None, Some(remote)) => syn::parse_str::<syn::Type>(remote).map(Some),
+        (None, None) => Ok(None),
+    };
+    let remote = match remote {
+        Ok(remote) => remote,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
-    let name = id.to_string();
-    let ref_name = format!("#/components/schemas/{}", id);
+    // Each instantiation of a generic type gets its own component, e.g. `Page_User`.
+    let name = match (&attr.rename, &remote, &serde.rename) {
+        (Some(rename), _, _) => quote! { #rename.to_string() },
+        (None, Some(remote), _) => quote! { ::oasgen::core::remote_name::<#remote>() },
+        (None, None, serde_rename) => {
+            let name = serde_rename.clone().unwrap_or_else(|| id.to_string());
+            quote! { ::oasgen::core::component_name(#name, module_path!()) }
+        }
+    };
+    let type_params = ast.generics.type_params().map(|param| &param.ident).collect::<Vec<_>>();
+    // A remote type's name already names its generic arguments.
+    let name_params = type_params.iter().filter(|_| attr.rename.is_some() || remote.is_none());
+    // Type arguments go by their owner, so `Page<Box<User>>` owns the same component as
+    // `Page<User>`.
+    let schema_owner = match &remote {
+        Some(remote) => quote! { ::std::any::type_name::<#remote>().to_string() },
+        None if type_params.is_empty() => quote! { ::std::any::type_name::<Self>().to_string() },
+        None => quote! {
+            ::oasgen::core::generic_owner(
+                ::std::any::type_name::<Self>(),
+                &[#(<#type_params as ::oasgen::OaSchema>::schema_owner()),*],
+            )
+        },
+    };
+    let generics = add_trait_bounds(&ast.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let expanded = quote! {
-        impl ::oasgen::OaSchema for #id {
-            fn schema_name() -> Option<&'static str> {
-                Some(#name)
+        #[allow(deprecated)]
+        impl #impl_generics ::oasgen::OaSchema for #id #ty_generics #where_clause {
+            fn schema_name() -> Option<String> {
+                Some([#name #(, <#name_params as ::oasgen::OaSchema>::type_name_part())*].join("_"))
+            }
+
+            fn schema_owner() -> String {
+                #schema_owner
             }
 
             fn schema_ref() -> Option<::oasgen::ReferenceOr<::oasgen::Schema>> {
-                Some(::oasgen::ReferenceOr::ref_(#ref_name))
+                Some(::oasgen::ReferenceOr::schema_ref(&<Self as ::oasgen::OaSchema>::schema_name().unwrap()))
             }
 
             fn schema() -> Option<::oasgen::Schema> {
+                let schema: Option<::oasgen::Schema> = { #schema };
+                schema.map(|mut schema| {
+                    #describe
+                    #example
+                    #deprecated
+                    schema
+                })
+            }
+
+            fn register(registry: &mut ::oasgen::SchemaRegistry) {
+                let name = <Self as ::oasgen::OaSchema>::schema_name().unwrap();
+                // Registering ourselves first ends the recursion for types that refer to themselves.
+                if !registry.claim_as(&name, &<Self as ::oasgen::OaSchema>::schema_owner()) {
+                    return;
+                }
+                registry.insert(&name, <Self as ::oasgen::OaSchema>::schema().unwrap());
+                #register
+            }
+        }
+    };
+    TokenStream::from(expanded)
+}
+
+/// Generated bodies for the `OaSchema` impl of a derived type.
+struct DerivedSchema {
+    /// Body of `schema()`
+    schema: TokenStream2,
+    /// Statements in `register()` that add the components the type relies on, after the type
+    /// itself is registered.
+    register: TokenStream2,
+}
+
+fn derive_struct_schema(ast: &DeriveInput, serde: &SerdeAttributes, attr: &OpenApiContainerAttributes) -> syn::Result<DerivedSchema> {
+    if let Some(field) = newtype_field(ast, serde) {
+        // A component which wraps the inner type's schema.
+        let field = FieldInfo::new(field)?;
+        let schema = field.schema("newtype field");
+        return Ok(DerivedSchema {
+            schema: quote! { Some(::oasgen::core::into_schema(#schema)) },
+            register: field.register().unwrap_or_default(),
+        });
+    }
+    let schema = match util::get_fields(ast) {
+        Fields::Named(fields) => {
+            let properties = fields::object_properties(&fields.named, serde.rename_all, serde.default)?;
+            let additional_properties = attr.additional_properties.as_ref().map(|allowed| allowed.value)
+                .or(serde.deny_unknown_fields.then_some(false))
+                .map(|allowed| quote! {
+                    ::oasgen::core::set_additional_properties(&mut o, #allowed);
+                });
+            quote! {
                 let mut o = ::oasgen::Schema::new_object();
-                #(#properties)*
-                let req = o.required_mut().unwrap();
-                *req = #required;
+                #properties
+                #additional_properties
                 Some(o)
             }
         }
+        Fields::Unnamed(fields) => {
+            let schema = fields::tuple_schema(&fields.unnamed)?;
+            quote! { Some(#schema) }
+        }
+        Fields::Unit => return Err(syn::Error::new_spanned(ast, "#[derive(OaSchema)] cannot be used on unit structs")),
     };
-    TokenStream::from(expanded)
+    Ok(DerivedSchema {
+        schema,
+        register: fields::register_fields(util::get_fields(ast))?,
+    })
+}
+
+/// The single field a struct serializes as, for newtype structs and `#[serde(transparent)]`.
+fn newtype_field<'a>(ast: &'a DeriveInput, serde: &SerdeAttributes) -> Option<&'a syn::Field> {
+    let Data::Struct(data) = &ast.data else {
+        return None;
+    };
+    match &data.fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => fields.unnamed.first(),
+        Fields::Named(fields) if serde.transparent => {
+            let mut fields = fields.named.iter().filter(|f| {
+                !FieldInfo::new(f).map(|f| f.is_skipped()).unwrap_or(false)
+            });
+            let field = fields.next();
+            if fields.next().is_some() {
+                return None;
+            }
+            field
+        }
+        _ => None,
+    }
+}
+
+/// Every type parameter of a derived type needs to implement `OaSchema` itself.
+fn add_trait_bounds(generics: &syn::Generics) -> syn::Generics {
+    let mut generics = generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(syn::parse_quote!(::oasgen::OaSchema));
+    }
+    generics
 }
 
+/// For `#[openapi(inline)]` newtypes and `#[openapi(as = ..)]`, the impl forwards everything to
+/// the other type. `customize` holds statements that describe the type's own schema, like its doc
+/// comment, which apply to the other type's schema, wrapping a reference with `into_schema`.
+fn derive_forwarding(ast: &DeriveInput, ty: &syn::Type, customize: TokenStream2) -> TokenStream2 {
+    let id = &ast.ident;
+    let generics = add_trait_bounds(&ast.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (schema_ref, schema) = if customize.is_empty() {
+        (
+            quote! { <#ty as ::oasgen::OaSchema>::schema_ref() },
+            quote! { <#ty as ::oasgen::OaSchema>::schema() },
+        )
+    } else {
+        (
+            quote! {
+                <#ty as ::oasgen::OaSchema>::schema_ref().map(|schema| {
+                    let mut schema = ::oasgen::core::into_schema(schema);
+                    #customize
+                    ::oasgen::ReferenceOr::Item(schema)
+                })
+            },
+            quote! {
+                <#ty as ::oasgen::OaSchema>::schema().map(|mut schema| {
+                    #customize
+                    schema
+                })
+            },
+        )
+    };
+    quote! {
+        #[allow(deprecated)]
+        impl #impl_generics ::oasgen::OaSchema for #id #ty_generics #where_clause {
+            fn schema_name() -> Option<String> {
+                <#ty as ::oasgen::OaSchema>::schema_name()
+            }
+
+            fn type_name_part() -> String {
+                <#ty as ::oasgen::OaSchema>::type_name_part()
+            }
+
+            fn schema_owner() -> String {
+                <#ty as ::oasgen::OaSchema>::schema_owner()
+            }
+
+            fn schema_ref() -> Option<::oasgen::ReferenceOr<::oasgen::Schema>> {
+                #schema_ref
+            }
+
+            fn schema() -> Option<::oasgen::Schema> {
+                #schema
+            }
+
+            fn register(registry: &mut ::oasgen::SchemaRegistry) {
+                <#ty as ::oasgen::OaSchema>::register(registry)
+            }
+        }
+    }
+}
 
 #[proc_macro_attribute]
 pub fn openapi(_args: TokenStream, input: TokenStream) -> TokenStream {
@@ -82,9 +300,9 @@ pub fn openapi(_args: TokenStream, input: TokenStream) -> TokenStream {
     );
 
     let block = &ast.block;
-    ast.block = Box::new(syn::parse2(quote!({
+    *ast.block = syn::parse2(quote!({
         ::oasgen::TypedResponseFuture::new(async move #block)
-    })).expect("parsing empty block"));
+    })).expect("parsing empty block");
 
     let public = ast.vis.clone();
 
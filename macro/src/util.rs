@@ -1,18 +1,98 @@
-use syn::punctuated::Punctuated;
-use syn::token::Comma;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
 use syn::Data::Struct;
-use syn::{DataStruct, DeriveInput, Field, Fields, FieldsNamed};
+use syn::{Attribute, DataStruct, DeriveInput, Expr, ExprLit, Fields, Lit, LitStr, Meta};
 
 
 /// Given derive input of a struct, get the fields of the struct.
-pub fn get_fields(ast: &DeriveInput) -> &Punctuated<Field, Comma> {
-    let fields = match &ast.data {
+pub fn get_fields(ast: &DeriveInput) -> &Fields {
+    match &ast.data {
         Struct(DataStruct { ref fields, .. }) => fields,
-        _ => panic!("#[ormlite] can only be used on structs"),
+        _ => panic!("#[derive(OaSchema)] can only be used on structs and enums"),
+    }
+}
+
+/// The doc comment of an item, with the leading space rustdoc strips removed from each line.
+///
+/// Blank lines and indentation are kept as they are, so markdown renders the same as in rustdoc.
+pub fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let mut lines = Vec::new();
+    for attr in attrs {
+        let Meta::NameValue(meta) = &attr.meta else { continue };
+        if !meta.path.is_ident("doc") {
+            continue;
+        }
+        let Expr::Lit(ExprLit { lit: Lit::Str(doc), .. }) = &meta.value else { continue };
+        lines.extend(doc.value().split('\n').map(String::from));
+    }
+    let indent = lines.iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()?;
+    let doc = lines.iter()
+        .map(|line| line.get(indent..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(doc.trim_matches('\n').to_string())
+}
+
+/// Statements that set the `title` and `description` of the schema bound to `schema`, using the
+/// `#[openapi(..)]` overrides if present, or the doc comment.
+pub fn describe(attrs: &[Attribute], title: Option<&LitStr>, description: Option<&LitStr>) -> TokenStream2 {
+    let title = title.map(|title| quote! {
+        schema.schema_data.title = Some(#title.to_string());
+    });
+    let description = description.map(LitStr::value).or_else(|| doc_comment(attrs)).map(|description| quote! {
+        schema.schema_data.description = Some(#description.to_string());
+    });
+    quote! { #title #description }
+}
+
+/// Statement that sets the `example` of the schema bound to `schema`, from `#[openapi(example)]`
+/// or by serializing the value `#[openapi(example_fn)]` returns.
+pub fn example(example: Option<&Expr>, example_fn: Option<&LitStr>) -> syn::Result<Option<TokenStream2>> {
+    let value = match (example, example_fn) {
+        (Some(example), _) => example.clone(),
+        (None, Some(example_fn)) => {
+            let example_fn: syn::Path = example_fn.parse()?;
+            syn::parse_quote!(#example_fn())
+        }
+        (None, None) => return Ok(None),
     };
-    let fields = match fields {
-        Fields::Named(FieldsNamed { named, .. }) => named,
-        _ => panic!("#[ormlite] can only be used on structs with named fields"),
+    Ok(Some(quote! {
+        schema.schema_data.example = Some(::oasgen::core::serde_json::to_value(#value).unwrap());
+    }))
+}
+
+/// Statement that marks the schema bound to `schema` as deprecated, if `#[openapi(deprecated)]`
+/// or Rust's own `#[deprecated]` is present.
+pub fn deprecated(attrs: &[Attribute], deprecated: bool) -> Option<TokenStream2> {
+    let deprecated = deprecated || attrs.iter().any(|attr| attr.path().is_ident("deprecated"));
+    deprecated.then(|| quote! {
+        schema.schema_data.deprecated = true;
+    })
+}
+
+/// If `ty` is written as an `Option<..>`, the type inside it.
+fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
     };
-    fields
-}
\ No newline at end of file
+    match args.args.first()? {
+        syn::GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
+
+/// Whether `ty` is an `Option<Option<..>>`, which tells a missing value apart from `null`, like
+/// with `serde_with::rust::double_option`.
+pub fn is_double_option(ty: &syn::Type) -> bool {
+    option_inner(ty).and_then(option_inner).is_some()
+}
@@ -7,7 +7,7 @@ mod format;
 pub use openapiv3::*;
 pub use format::*;
 pub use oasgen_macro::{OaSchema, openapi};
-pub use oasgen_core::{OaSchema, TypedResponseFuture, FunctionMetadata};
+pub use oasgen_core::{OaSchema, SchemaRegistry, TypedResponseFuture, FunctionMetadata};
 pub use oasgen_core as core;
 pub use server::Server;
 
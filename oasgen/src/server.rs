@@ -6,15 +6,16 @@ mod actix;
 mod axum;
 mod none;
 
+use std::collections::{HashMap, HashSet};
 use std::env::var;
 use std::future::Future;
 use std::marker::PhantomData;
 use std::path::Path;
 use std::sync::Arc;
 use http::Method;
-use openapiv3::{Components, OpenAPI, ReferenceOr};
+use openapiv3::{AdditionalProperties, Components, OpenAPI, ReferenceOr, Schema, SchemaKind, Type};
 
-use oasgen_core::{OaOperation, OaSchema};
+use oasgen_core::{OaOperation, OaSchema, SchemaRegistry};
 
 pub struct Server<Router, Mutability = OpenAPI> {
     router: Router,
@@ -28,6 +29,16 @@ pub struct Server<Router, Mutability = OpenAPI> {
     pub json_route: Option<String>,
     /// Configuration to serve the spec as YAML
     pub yaml_route: Option<String>,
+    /// `additionalProperties` for object schemas in `components/schemas` that don't set it
+    /// themselves. See [`Server::additional_properties`].
+    pub additional_properties: Option<bool>,
+    /// Whether component names include the module path. See [`Server::qualify_component_names`].
+    qualify_component_names: bool,
+    /// Every component registered so far, which tells which type owns each component name.
+    registry: SchemaRegistry,
+    /// Schemas that replace the ones of certain types, keyed by `std::any::type_name`. See
+    /// [`Server::override_schema`].
+    schema_overrides: HashMap<&'static str, Schema>,
 
     #[cfg(feature = "swagger-ui")]
     #[cfg_attr(docsrs, doc(cfg(feature = "swagger-ui")))]
@@ -46,6 +57,10 @@ impl<Router: Clone> Clone for Server<Router, Arc<OpenAPI>> {
             openapi: self.openapi.clone(),
             json_route: self.json_route.clone(),
             yaml_route: self.yaml_route.clone(),
+            additional_properties: self.additional_properties,
+            qualify_component_names: self.qualify_component_names,
+            registry: self.registry.clone(),
+            schema_overrides: self.schema_overrides.clone(),
             prefix: self.prefix.clone(),
             #[cfg(feature = "swagger-ui")]
             swagger_ui_route: self.swagger_ui_route.clone(),
@@ -55,6 +70,12 @@ impl<Router: Clone> Clone for Server<Router, Arc<OpenAPI>> {
     }
 }
 
+impl<Router: Default> Default for Server<Router, OpenAPI> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<Router: Default> Server<Router, OpenAPI> {
     pub fn new() -> Self {
         Self {
@@ -65,6 +86,10 @@ impl<Router: Default> Server<Router, OpenAPI> {
             router: Router::default(),
             json_route: None,
             yaml_route: None,
+            additional_properties: None,
+            qualify_component_names: false,
+            registry: SchemaRegistry::new(),
+            schema_overrides: HashMap::new(),
             prefix: None,
             #[cfg(feature = "swagger-ui")]
             swagger_ui_route: None,
@@ -78,32 +103,74 @@ impl<Router: Default> Server<Router, OpenAPI> {
         where
             F: OaOperation<Signature>,
     {
-        let mut path = path.to_string();
-        if path.contains(':') {
-            use once_cell::sync::OnceCell;
-            use regex::Regex;
-            static REMAP: OnceCell<Regex> = OnceCell::new();
-            let remap = REMAP.get_or_init(|| Regex::new("/:([a-zA-Z0-9_]+)/").unwrap());
-            path = remap.replace_all(&path, "/{$1}/").to_string();
-        }
-        let item = self.openapi.paths.paths.entry(path.to_string()).or_default();
+        let path = openapi_path(path);
+        let qualify = self.qualify_component_names;
+        let mut operation = oasgen_core::with_qualified_names(qualify, F::operation);
+        oasgen_core::bind_path_parameters(&path, &mut operation);
+        let item = self.openapi.paths.paths.entry(path).or_default();
         let item = item.as_mut().expect("Currently don't support references for PathItem");
         match method.as_str() {
-            "GET" => item.get = Some(F::operation()),
-            "POST" => item.post = Some(F::operation()),
-            "PUT" => item.put = Some(F::operation()),
-            "DELETE" => item.delete = Some(F::operation()),
-            "OPTIONS" => item.options = Some(F::operation()),
-            "HEAD" => item.head = Some(F::operation()),
-            "PATCH" => item.patch = Some(F::operation()),
-            "TRACE" => item.trace = Some(F::operation()),
+            "GET" => item.get = Some(operation),
+            "POST" => item.post = Some(operation),
+            "PUT" => item.put = Some(operation),
+            "DELETE" => item.delete = Some(operation),
+            "OPTIONS" => item.options = Some(operation),
+            "HEAD" => item.head = Some(operation),
+            "PATCH" => item.patch = Some(operation),
+            "TRACE" => item.trace = Some(operation),
             _ => panic!("Unsupported method: {}", method),
         }
 
-        for reference in F::references() {
-            if !self.openapi.schemas().contains_key(reference) {
-                let schema = F::referenced_schema(reference);
-                self.openapi.schemas_mut().insert(reference.to_string(), ReferenceOr::Item(schema));
+        oasgen_core::with_qualified_names(qualify, || F::register_schemas(&mut self.registry));
+        self.add_components();
+    }
+
+    /// Add the registered components that aren't in the spec yet. Components that turn out to be
+    /// combined with others through `allOf` lose the `additionalProperties` default.
+    fn add_components(&mut self) {
+        let composed = composed_components(&self.registry, &self.schema_overrides);
+        let mut names = self.registry.claimed().map(str::to_string).collect::<Vec<_>>();
+        for (name, _) in self.registry.iter() {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        for name in names {
+            let reopen = self.additional_properties.is_some() && composed.contains(&name);
+            if self.openapi.schemas().contains_key(&name) && !reopen {
+                continue;
+            }
+            // A type without a schema, like a `Remote` type, can still get one from
+            // `override_schema`, up until the spec is frozen.
+            let Some(schema) = self.component_schema(&name, &composed) else {
+                continue;
+            };
+            self.openapi.schemas_mut().insert(name, ReferenceOr::Item(schema));
+        }
+    }
+
+    /// The schema of a registered component, from `override_schema` or the type itself, with the
+    /// `additionalProperties` default.
+    fn component_schema(&self, name: &str, composed: &HashSet<String>) -> Option<Schema> {
+        let owner = self.registry.owner(name);
+        let mut schema = owner.and_then(|owner| self.schema_overrides.get(owner))
+            .or_else(|| self.registry.get(name))?
+            .clone();
+        if let Some(allowed) = self.additional_properties {
+            default_additional_properties(&mut schema, allowed, !composed.contains(name));
+        }
+        Some(schema)
+    }
+
+    /// Panics if a component the routes use has no schema, because it's the component of a type
+    /// without one, and no `override_schema` gave it one.
+    fn assert_components(&self) {
+        for name in self.registry.claimed() {
+            if !self.openapi.schemas().contains_key(name) {
+                panic!(
+                    "No schema for `{}`, which is used as component `{}`. Add one with Server::override_schema.",
+                    self.registry.owner(name).unwrap_or_default(), name,
+                );
             }
         }
     }
@@ -138,6 +205,59 @@ impl<Router: Default> Server<Router, OpenAPI> {
         self
     }
 
+    /// Set `additionalProperties` on every object schema in `components/schemas` that doesn't set it
+    /// itself, including those of routes added later, and the objects inlined in them, like
+    /// struct variants of enums. Use `false` to have clients reject unknown keys everywhere, as if
+    /// every type had `#[serde(deny_unknown_fields)]`.
+    ///
+    /// Objects combined with others through `allOf`, like the fields of a struct with a flattened
+    /// enum and the enum's variants, are left open, since each would reject the keys of the others.
+    ///
+    /// Individual types can still opt out with `#[openapi(additional_properties = true)]`.
+    pub fn additional_properties(mut self, allowed: bool) -> Self {
+        self.additional_properties = Some(allowed);
+        let composed = composed_components(&self.registry, &self.schema_overrides);
+        for (name, schema) in self.openapi.schemas_mut() {
+            if let ReferenceOr::Item(schema) = schema {
+                default_additional_properties(schema, allowed, !composed.contains(name));
+            }
+        }
+        self
+    }
+
+    /// Use `schema` for the component of type `T`, in place of the one `T` provides, including for
+    /// components that are already in the spec. It can be called before or after the routes that
+    /// use `T`, as long as it's before the spec is frozen or written.
+    ///
+    /// This is also how types from other crates get a schema. Refer to them with
+    /// `#[openapi(as = oasgen::core::Remote<other_crate::Money>)]`.
+    pub fn override_schema<T: ?Sized>(mut self, schema: Schema) -> Self {
+        let owner = std::any::type_name::<T>();
+        self.schema_overrides.insert(owner, schema);
+        let names = self.registry.claimed()
+            .filter(|name| self.registry.owner(name) == Some(owner))
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        for name in names {
+            self.openapi.schemas_mut().shift_remove(&name);
+        }
+        self.add_components();
+        self
+    }
+
+    /// Name the components of derived types after their module path as well, so `billing::Invoice`
+    /// and `admin::Invoice` become `billing.Invoice` and `admin.Invoice` instead of colliding.
+    /// Types with `#[openapi(rename = "..")]` keep the name they are given.
+    ///
+    /// This only affects this server, and must be called before any routes are added. Names are
+    /// qualified while routes are added; to compute a component name or reference for this spec
+    /// elsewhere, use [`oasgen_core::with_qualified_names`].
+    pub fn qualify_component_names(mut self) -> Self {
+        assert!(self.openapi.schemas().is_empty(), "qualify_component_names must be called before adding routes.");
+        self.qualify_component_names = true;
+        self
+    }
+
     /// Configure a prefix to mount the API routes (including the OpenAPI spec routes) under.
     pub fn prefix(mut self, prefix: &str) -> Self {
         self.prefix = Some(prefix.to_string());
@@ -181,6 +301,7 @@ impl<Router: Default> Server<Router, OpenAPI> {
     pub fn write_and_exit_if_env_var_set<P: AsRef<Path>>(self, path: P) -> Self {
         let path = path.as_ref();
         if var("OASGEN_WRITE_SPEC").map(|s| s == "1").unwrap_or(false) {
+            self.assert_components();
             let spec = if path.extension().map(|e| e == "json").unwrap_or(false) {
                 serde_json::to_string(&self.openapi).expect("Serializing OpenAPI spec to JSON failed.")
             } else {
@@ -197,11 +318,16 @@ impl<Router: Default> Server<Router, OpenAPI> {
     /// Functionally, it moves the OpenAPI spec into an Arc, so that view handlers (which are async
     /// and therefore have undetermined lifespans) can hold onto it.
     pub fn freeze(self) -> Server<Router, Arc<OpenAPI>> {
+        self.assert_components();
         Server {
             router: self.router,
             openapi: Arc::new(self.openapi),
             json_route: self.json_route,
             yaml_route: self.yaml_route,
+            additional_properties: self.additional_properties,
+            qualify_component_names: self.qualify_component_names,
+            registry: self.registry,
+            schema_overrides: self.schema_overrides,
             prefix: self.prefix,
             #[cfg(feature = "swagger-ui")]
             swagger_ui_route: self.swagger_ui_route,
@@ -209,4 +335,100 @@ impl<Router: Default> Server<Router, OpenAPI> {
             swagger_ui: self.swagger_ui,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Write a route in OpenAPI's template syntax, so axum's `/users/:id` and actix's `/users/{id:\d+}`
+/// both become `/users/{id}`, and axum's wildcard `/files/*path` becomes `/files/{path}`.
+fn openapi_path(path: &str) -> String {
+    use once_cell::sync::OnceCell;
+    use regex::Regex;
+    static SEGMENT: OnceCell<Regex> = OnceCell::new();
+    static PATTERN: OnceCell<Regex> = OnceCell::new();
+    let segment = SEGMENT.get_or_init(|| Regex::new("/[:*]([a-zA-Z0-9_]+)").unwrap());
+    let pattern = PATTERN.get_or_init(|| Regex::new(r"\{([a-zA-Z0-9_]+):[^}]*\}").unwrap());
+    let path = segment.replace_all(path, "/{$1}");
+    pattern.replace_all(&path, "{$1}").to_string()
+}
+
+/// Set `additionalProperties` on an object schema, and the objects inlined in it, where they don't
+/// set it themselves.
+///
+/// Parts of an `allOf` with several parts are left open, since each part is checked on its own,
+/// and would reject the keys of the others. So is the schema itself unless `close` is set, for
+/// components used that way.
+fn default_additional_properties(schema: &mut Schema, allowed: bool, close: bool) {
+    match &mut schema.schema_kind {
+        SchemaKind::Type(Type::Object(o)) => {
+            if close {
+                o.additional_properties.get_or_insert(AdditionalProperties::Any(allowed));
+            }
+            for property in o.properties.values_mut().filter_map(ReferenceOr::as_mut) {
+                default_additional_properties(property, allowed, true);
+            }
+        }
+        SchemaKind::Type(Type::Array(a)) => {
+            if let Some(ReferenceOr::Item(items)) = &mut a.items {
+                default_additional_properties(items, allowed, true);
+            }
+        }
+        SchemaKind::OneOf { one_of: schemas } | SchemaKind::AnyOf { any_of: schemas } => {
+            for schema in schemas.iter_mut().filter_map(ReferenceOr::as_mut) {
+                default_additional_properties(schema, allowed, close);
+            }
+        }
+        SchemaKind::AllOf { all_of } => {
+            let close = close && all_of.len() == 1;
+            for part in all_of.iter_mut().filter_map(ReferenceOr::as_mut) {
+                default_additional_properties(part, allowed, close);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The components that are parts of an `allOf` with several parts, like the variants of a
+/// flattened internally tagged enum, which have to stay open. See [`default_additional_properties`].
+fn composed_components(registry: &SchemaRegistry, overrides: &HashMap<&'static str, Schema>) -> HashSet<String> {
+    let source = |name: &str| registry.owner(name)
+        .and_then(|owner| overrides.get(owner))
+        .or_else(|| registry.get(name));
+    let mut composed = HashSet::new();
+    for name in registry.claimed() {
+        if let Some(schema) = source(name) {
+            find_composed(schema, false, &source, &mut composed);
+        }
+    }
+    composed
+}
+
+/// Add the components `schema` combines with others to `composed`. `part` tells whether `schema`
+/// is such a part itself.
+fn find_composed<'a>(schema: &'a Schema, part: bool, source: &dyn Fn(&str) -> Option<&'a Schema>, composed: &mut HashSet<String>) {
+    let (schemas, part) = match &schema.schema_kind {
+        SchemaKind::Type(Type::Object(o)) => (o.properties.values().collect::<Vec<_>>(), false),
+        SchemaKind::Type(Type::Array(a)) => {
+            if let Some(ReferenceOr::Item(items)) = &a.items {
+                find_composed(items, false, source, composed);
+            }
+            return;
+        }
+        SchemaKind::OneOf { one_of: schemas } | SchemaKind::AnyOf { any_of: schemas } => (schemas.iter().collect(), part),
+        SchemaKind::AllOf { all_of } => (all_of.iter().collect(), part || all_of.len() > 1),
+        _ => return,
+    };
+    for schema in schemas {
+        match schema {
+            ReferenceOr::Reference { reference } if part => {
+                let name = reference.rsplit('/').next().unwrap_or_default();
+                // The component's own variants are parts too.
+                if composed.insert(name.to_string()) {
+                    if let Some(schema) = source(name) {
+                        find_composed(schema, true, source, composed);
+                    }
+                }
+            }
+            ReferenceOr::Reference { .. } => {}
+            ReferenceOr::Item(schema) => find_composed(schema, part, source, composed),
+        }
+    }
+}
@@ -2,4 +2,6 @@
 fn run_tests() {
     let t = trybuild::TestCases::new();
     t.pass("tests/test-actix/01-hello.rs");
+    t.pass("tests/test-actix/02-path.rs");
+    t.pass("tests/test-actix/03-query.rs");
 }
\ No newline at end of file
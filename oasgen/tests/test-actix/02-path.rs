@@ -0,0 +1,48 @@
+use oasgen::{OaSchema, Server, openapi};
+use actix_web::web::{Json, Path};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, OaSchema)]
+pub struct Post {
+    pub title: String,
+}
+
+#[derive(Deserialize, OaSchema)]
+pub struct PostPath {
+    pub user_id: u64,
+    pub post_id: String,
+}
+
+#[openapi]
+async fn get_post(_path: Path<(u64, String)>) -> Json<Post> {
+    unimplemented!()
+}
+
+#[openapi]
+async fn update_post(_path: Path<PostPath>, _body: Json<Post>) -> Json<Post> {
+    unimplemented!()
+}
+
+#[openapi]
+async fn get_user(_path: Path<u64>) -> Json<Post> {
+    unimplemented!()
+}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::actix()
+        .get("/users/{id}/posts/{post_id:[a-z]+}", get_post)
+        .post("/users/{user_id}/posts/{post_id}", update_post)
+        .get("/users/{id}", get_user)
+        .freeze();
+    let spec = serde_yaml::to_string(&server.openapi.paths).unwrap();
+    assert_eq!(spec.trim(), include_str!("02-path.yaml"));
+
+    let count = std::panic::catch_unwind(|| Server::actix().get("/users/{id}", get_post));
+    let message = *count.err().unwrap().downcast::<String>().unwrap();
+    assert!(message.contains("but the handler extracts 2"), "{}", message);
+
+    let name = std::panic::catch_unwind(|| Server::actix().post("/users/{id}/posts/{post_id}", update_post));
+    let message = *name.err().unwrap().downcast::<String>().unwrap();
+    assert!(message.contains("`user_id` has no placeholder"), "{}", message);
+}
@@ -0,0 +1,59 @@
+use oasgen::{OaSchema, Server, openapi};
+use actix_web::web::{Json, Query};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, OaSchema)]
+pub struct Post {
+    pub title: String,
+}
+
+#[derive(Deserialize, OaSchema)]
+pub struct Filter {
+    pub author: Option<String>,
+    pub published: Option<bool>,
+}
+
+#[derive(Deserialize, OaSchema)]
+pub struct Search {
+    /// Text to search for.
+    pub q: String,
+    /// Only posts with all of these tags.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub filter: Option<Filter>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Deserialize, OaSchema)]
+#[serde(tag = "sort")]
+pub enum Order {
+    Date { since: Option<String> },
+    Author { name: String },
+}
+
+#[derive(Deserialize, OaSchema)]
+pub struct Listing {
+    pub q: String,
+    #[serde(flatten)]
+    pub order: Order,
+}
+
+#[openapi]
+async fn list(_query: Query<Listing>) -> Json<Vec<Post>> {
+    unimplemented!()
+}
+
+#[openapi]
+async fn search(_query: Query<Search>) -> Json<Vec<Post>> {
+    unimplemented!()
+}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::actix()
+        .get("/posts", search)
+        .get("/listing", list)
+        .freeze();
+    let spec = serde_yaml::to_string(&server.openapi.paths).unwrap();
+    assert_eq!(spec.trim(), include_str!("03-query.yaml"));
+}
@@ -0,0 +1,5 @@
+#[test]
+fn run_tests() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/test-axum/01-params.rs");
+}
@@ -0,0 +1,57 @@
+use axum::extract::{Path, Query};
+use axum::Json;
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, OaSchema)]
+pub struct Post {
+    pub title: String,
+}
+
+#[derive(Deserialize, OaSchema)]
+pub struct PostPath {
+    pub user_id: u64,
+    pub post_id: String,
+}
+
+#[derive(Deserialize, OaSchema)]
+pub enum Order {
+    Newest,
+    Oldest,
+}
+
+#[derive(Deserialize, OaSchema)]
+pub struct ListPosts {
+    /// Text to search for.
+    pub search: String,
+    pub order: Option<Order>,
+    #[serde(default)]
+    pub page: u32,
+}
+
+#[openapi]
+async fn get_post(Path((_user, _post)): Path<(u64, String)>) -> Json<Post> {
+    unimplemented!()
+}
+
+#[openapi]
+async fn update_post(Path(_path): Path<PostPath>, Json(_body): Json<Post>) -> Json<Post> {
+    unimplemented!()
+}
+
+#[openapi]
+async fn list_posts(Path(_user): Path<u64>, Query(_query): Query<ListPosts>) -> Json<Vec<Post>> {
+    unimplemented!()
+}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::axum()
+        .get("/users/:id/posts/:post_id", get_post)
+        .put("/authors/:user_id/posts/:post_id", update_post)
+        .get("/users/:id/posts", list_posts)
+        .freeze();
+    let spec = serde_yaml::to_string(&server.openapi.paths).unwrap();
+    assert_eq!(spec.trim(), include_str!("01-params.yaml"));
+    let _router: axum::Router = server.into_router();
+}
@@ -3,4 +3,27 @@ fn run_tests() {
     let t = trybuild::TestCases::new();
     t.pass("tests/test-none/01-hello.rs");
     t.pass("tests/test-none/02-required.rs");
+    t.pass("tests/test-none/03-enum.rs");
+    t.pass("tests/test-none/04-data-enum.rs");
+    t.pass("tests/test-none/05-newtype.rs");
+    t.pass("tests/test-none/06-generic.rs");
+    t.pass("tests/test-none/07-rename.rs");
+    t.pass("tests/test-none/08-flatten.rs");
+    t.pass("tests/test-none/09-default.rs");
+    t.pass("tests/test-none/10-docs.rs");
+    t.pass("tests/test-none/11-constraints.rs");
+    #[cfg(feature = "validator")]
+    t.pass("tests/test-none/12-validator.rs");
+    t.pass("tests/test-none/13-nested.rs");
+    t.pass("tests/test-none/14-recursive.rs");
+    t.pass("tests/test-none/15-override.rs");
+    t.pass("tests/test-none/16-example.rs");
+    t.pass("tests/test-none/17-access.rs");
+    t.pass("tests/test-none/18-additional-properties.rs");
+    t.pass("tests/test-none/19-collections.rs");
+    t.pass("tests/test-none/20-nullable.rs");
+    t.pass("tests/test-none/21-any.rs");
+    t.pass("tests/test-none/22-component-names.rs");
+    t.pass("tests/test-none/23-qualified-names.rs");
+    t.pass("tests/test-none/24-remote.rs");
 }
\ No newline at end of file
@@ -0,0 +1,21 @@
+use oasgen::{OaSchema};
+use serde::{Deserialize, Serialize};
+
+#[derive(OaSchema, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Active,
+    PendingReview,
+    #[serde(rename = "gone")]
+    Deleted,
+    #[serde(skip)]
+    #[allow(dead_code)]
+    Internal,
+}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let schema = Status::schema().unwrap();
+    let spec = serde_yaml::to_string(&schema).unwrap();
+    assert_eq!(spec.trim(), include_str!("03-enum.yaml"));
+}
@@ -0,0 +1,58 @@
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Circle {
+    radius: f64,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub enum External {
+    Empty,
+    Circle(Circle),
+    Point(i32, i32),
+    Rect { width: f64, height: f64 },
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Internal {
+    Empty,
+    Circle(Circle),
+    Rect { width: f64, height: f64 },
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+#[serde(tag = "t", content = "c")]
+pub enum Adjacent {
+    Empty,
+    Circle(Circle),
+    Point(i32, i32),
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Untagged {
+    Number(f64),
+    Text(String),
+    Rect { width: f64, height: f64 },
+}
+
+#[openapi]
+async fn external(_body: External) -> Internal {
+    Internal::Empty
+}
+
+#[openapi]
+async fn adjacent(_body: Adjacent) -> Untagged {
+    Untagged::Number(1.0)
+}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .post("/external", external)
+        .post("/adjacent", adjacent);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("04-data-enum.yaml"));
+}
@@ -0,0 +1,46 @@
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct UserId(String);
+
+/// An email address.
+#[derive(OaSchema, Serialize, Deserialize)]
+#[openapi(inline, example = "jane@example.com")]
+pub struct Email(String);
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Point(i32, i32);
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Pair(String, i64);
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Line {
+    start: Point,
+    end: Point,
+    label: Pair,
+}
+
+#[openapi]
+async fn get_user(_id: UserId) -> Email {
+    Email("user@example.com".to_string())
+}
+
+#[openapi]
+async fn get_line(_pair: Pair) -> Line {
+    Line {
+        start: Point(0, 0),
+        end: Point(1, 1),
+        label: Pair("label".to_string(), 0),
+    }
+}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .post("/user", get_user)
+        .post("/line", get_line);
+    let spec = serde_yaml::to_string(&server.openapi).unwrap();
+    assert_eq!(spec.trim(), include_str!("05-newtype.yaml"));
+}
@@ -0,0 +1,61 @@
+use std::marker::PhantomData;
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+
+#[derive(OaSchema, Serialize, Deserialize, Clone)]
+pub struct User {
+    name: String,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Order {
+    total: i64,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Page<T> {
+    items: Vec<T>,
+    next: Option<String>,
+}
+
+#[derive(OaSchema, Serialize)]
+pub struct Tagged<'a, T, U>
+    where
+        T: Clone,
+{
+    value: T,
+    tags: Vec<U>,
+    #[serde(skip)]
+    #[openapi(skip)]
+    marker: PhantomData<&'a ()>,
+}
+
+#[derive(OaSchema, Serialize)]
+pub struct Pages {
+    users: Page<User>,
+    maybe_users: Page<Option<User>>,
+    boxed_users: Page<Box<User>>,
+}
+
+#[openapi]
+async fn list_users(_page: Page<Order>) -> Page<User> {
+    Page { items: vec![], next: None }
+}
+
+#[openapi]
+async fn save_pages(_pages: Pages) -> () {}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    assert_eq!(Page::<User>::schema_name().unwrap(), "Page_User");
+    assert_eq!(Tagged::<'static, User, String>::schema_name().unwrap(), "Tagged_User_String");
+    assert_eq!(Page::<Vec<i64>>::schema_name().unwrap(), "Page_Vec_i64");
+    assert_eq!(Page::<Option<User>>::schema_name().unwrap(), "Page_Option_User");
+    assert_eq!(Page::<Box<User>>::schema_name().unwrap(), "Page_User");
+
+    let server = Server::none()
+        .post("/users", list_users)
+        .post("/pages", save_pages);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("06-generic.yaml"));
+}
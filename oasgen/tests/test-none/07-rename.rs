@@ -0,0 +1,41 @@
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+
+#[derive(OaSchema, Serialize, Deserialize)]
+#[serde(rename = "Account", rename_all = "camelCase")]
+pub struct User {
+    user_id: i32,
+    #[serde(rename = "email")]
+    email_address: String,
+    #[serde(rename(serialize = "displayName", deserialize = "name"))]
+    name: String,
+    #[serde(alias = "kind")]
+    r#type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nick_name: Option<String>,
+    #[serde(skip)]
+    password_hash: String,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", rename_all_fields = "camelCase")]
+pub enum Event {
+    UserCreated { user_id: i32 },
+    #[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+    UserDeleted { user_id: i32, deleted_by: i32 },
+    #[serde(rename = "ping")]
+    Heartbeat,
+}
+
+#[openapi]
+async fn create_user(_body: User) -> Event {
+    Event::Heartbeat
+}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .post("/user", create_user);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("07-rename.yaml"));
+}
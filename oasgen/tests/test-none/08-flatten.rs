@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Timestamps {
+    created_at: String,
+    updated_at: Option<String>,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct AuditInfo {
+    created_by: String,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum Owner {
+    User { id: i32 },
+    Team { id: i32 },
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Document {
+    id: i32,
+    #[serde(flatten)]
+    timestamps: Timestamps,
+    #[serde(flatten)]
+    audit: Option<AuditInfo>,
+    #[serde(flatten)]
+    extra: HashMap<String, String>,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct OwnedDocument {
+    title: String,
+    #[serde(flatten)]
+    owner: Owner,
+    #[serde(flatten)]
+    timestamps: Timestamps,
+}
+
+/// Flattened, but still a component, since its replies refer to it.
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Thread {
+    title: String,
+    replies: Vec<Thread>,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Topic {
+    pinned: bool,
+    #[serde(flatten)]
+    thread: Thread,
+}
+
+#[openapi]
+async fn create_topic(_body: Topic) -> () {}
+
+#[openapi]
+async fn create_document(_body: Document) -> OwnedDocument {
+    unimplemented!()
+}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .post("/document", create_document)
+        .post("/topic", create_topic);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("08-flatten.yaml"));
+}
@@ -0,0 +1,40 @@
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+
+fn default_page_size() -> u32 {
+    20
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Search {
+    query: String,
+    #[serde(default)]
+    include_archived: bool,
+    #[serde(default = "default_page_size")]
+    #[openapi(default = 20)]
+    page_size: u32,
+    #[serde(default)]
+    #[openapi(default = "relevance")]
+    sort: String,
+}
+
+#[derive(OaSchema, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Settings {
+    theme: String,
+    #[openapi(default = true)]
+    notifications: bool,
+}
+
+#[openapi]
+async fn search(_body: Search) -> Settings {
+    Settings::default()
+}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .post("/search", search);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("09-default.yaml"));
+}
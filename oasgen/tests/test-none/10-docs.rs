@@ -0,0 +1,42 @@
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+
+/// A registered user.
+///
+/// Users are created on **sign up**, and can:
+///
+/// - log in
+/// - manage their `profile`
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct User {
+    /// Unique id, assigned by the server.
+    id: i32,
+    /// The display name.
+    #[openapi(title = "Name", description = "Shown next to everything the user posts.")]
+    name: String,
+    email: String,
+}
+
+/** Where the payment came from. */
+#[derive(OaSchema, Serialize, Deserialize)]
+#[serde(tag = "type")]
+#[openapi(title = "Payment source")]
+pub enum PaymentSource {
+    /// A stored card.
+    Card { last4: String },
+    /// A bank transfer.
+    Bank { iban: String },
+}
+
+#[openapi]
+async fn create_user(_body: User) -> PaymentSource {
+    unimplemented!()
+}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .post("/user", create_user);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("10-docs.yaml"));
+}
@@ -0,0 +1,39 @@
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+
+const MAX_TAGS: usize = 10;
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Username(#[openapi(min_length = 3, max_length = 32, pattern = "^[a-z0-9_]+$")] String);
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Product {
+    username: Username,
+    /// Constraints on a component apply next to its reference.
+    #[openapi(max_length = 16)]
+    nickname: Option<Username>,
+    #[openapi(min_length = 1, max_length = 64)]
+    name: String,
+    #[openapi(format = "email")]
+    contact: Option<String>,
+    #[openapi(exclusive_minimum = 0, maximum = 1000000, multiple_of = 0.01)]
+    price: f64,
+    #[openapi(minimum = -10, maximum = 10, format = "int32")]
+    rating: i32,
+    /// Fractional bounds are rounded to the integers they allow.
+    #[openapi(minimum = 0.5, exclusive_maximum = 99.5)]
+    stock: u32,
+    #[openapi(min_items = 1, max_items = MAX_TAGS, unique_items)]
+    tags: Vec<String>,
+}
+
+#[openapi]
+async fn create_product(_body: Product) -> () {}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .post("/product", create_product);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("11-constraints.yaml"));
+}
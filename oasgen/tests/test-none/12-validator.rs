@@ -0,0 +1,37 @@
+use oasgen::{OaSchema, Server, openapi};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+static SLUG: Lazy<Regex> = Lazy::new(|| Regex::new("^[a-z0-9-]+$").unwrap());
+
+#[derive(OaSchema, Validate, Serialize, Deserialize)]
+pub struct SignUp {
+    #[validate(length(min = 1, max = 100))]
+    name: String,
+    #[validate(email)]
+    email: String,
+    #[validate(url)]
+    website: Option<String>,
+    #[validate(range(min = 13, max = 130))]
+    age: u32,
+    #[validate(range(exclusive_min = 0.0))]
+    height: f64,
+    #[validate(regex(path = *SLUG))]
+    handle: String,
+    #[validate(length(max = 5))]
+    #[openapi(max_items = 3)]
+    interests: Vec<String>,
+}
+
+#[openapi]
+async fn sign_up(_body: SignUp) -> () {}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .post("/sign-up", sign_up);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("12-validator.yaml"));
+}
@@ -0,0 +1,41 @@
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub enum Country {
+    Us,
+    Ca,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Address {
+    street: String,
+    country: Country,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Customer {
+    name: String,
+    /// Where invoices are sent.
+    billing: Address,
+    shipping: Vec<Address>,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Item {
+    sku: String,
+    customer: Customer,
+}
+
+#[openapi]
+async fn create_items(_body: Vec<Item>) -> Vec<Customer> {
+    vec![]
+}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .post("/items", create_items);
+    let spec = serde_yaml::to_string(&server.openapi).unwrap();
+    assert_eq!(spec.trim(), include_str!("13-nested.yaml"));
+}
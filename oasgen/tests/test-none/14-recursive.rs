@@ -0,0 +1,52 @@
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Comment {
+    body: String,
+    replies: Vec<Comment>,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Node {
+    value: i32,
+    next: Option<Box<Node>>,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Employee {
+    name: String,
+    department: Department,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Department {
+    name: String,
+    manager: Box<Employee>,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+#[serde(tag = "op")]
+pub enum Expr {
+    Literal { value: f64 },
+    Add { left: Box<Expr>, right: Box<Expr> },
+}
+
+#[openapi]
+async fn comment(_body: Comment) -> Node {
+    unimplemented!()
+}
+
+#[openapi]
+async fn evaluate(_body: Expr) -> Employee {
+    unimplemented!()
+}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .post("/comment", comment)
+        .post("/evaluate", evaluate);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("14-recursive.yaml"));
+}
@@ -0,0 +1,52 @@
+use std::time::{Duration, SystemTime};
+use oasgen::{OaSchema, Schema, Server, openapi};
+
+fn duration_schema() -> Schema {
+    Schema::new_string().with_format("duration")
+}
+
+mod money {
+    pub fn schema() -> oasgen::Schema {
+        let mut schema = oasgen::Schema::new_string();
+        schema.schema_data.example = Some("12.50 USD".into());
+        schema
+    }
+}
+
+/// Serialized as a string like `"red"` or `"#ff0000"`.
+#[derive(OaSchema)]
+#[openapi(as = String)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+#[derive(OaSchema)]
+#[openapi(schema_with = "money::schema")]
+pub struct Money {
+    pub cents: i64,
+    pub currency: String,
+}
+
+#[derive(OaSchema)]
+pub struct Event {
+    /// Seconds since the Unix epoch.
+    #[openapi(as = i64)]
+    pub at: SystemTime,
+    #[openapi(schema_with = "duration_schema")]
+    pub duration: Duration,
+    pub color: Color,
+    pub price: Money,
+}
+
+#[openapi]
+async fn create_event(_body: Event) -> () {}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .post("/event", create_event);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("15-override.yaml"));
+}
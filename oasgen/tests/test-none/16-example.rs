@@ -0,0 +1,49 @@
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+
+#[derive(OaSchema, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[openapi(example = "admin")]
+pub enum Role {
+    Admin,
+    Member,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+#[openapi(example_fn = "User::example")]
+pub struct User {
+    #[openapi(example = "Ada Lovelace")]
+    name: String,
+    #[openapi(example = 36)]
+    age: u32,
+    #[openapi(example_fn = "default_roles")]
+    roles: Vec<Role>,
+    #[openapi(example = Role::Member)]
+    primary_role: Role,
+}
+
+impl User {
+    fn example() -> Self {
+        User {
+            name: "Grace Hopper".to_string(),
+            age: 85,
+            roles: vec![Role::Admin],
+            primary_role: Role::Admin,
+        }
+    }
+}
+
+fn default_roles() -> Vec<Role> {
+    vec![Role::Member]
+}
+
+#[openapi]
+async fn create_user(_body: User) -> () {}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .post("/user", create_user);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("16-example.yaml"));
+}
@@ -0,0 +1,36 @@
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+
+#[derive(OaSchema, Serialize, Deserialize)]
+#[deprecated(note = "Use User instead")]
+pub struct LegacyUser {
+    name: String,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct User {
+    #[openapi(read_only)]
+    id: i32,
+    #[serde(skip_deserializing)]
+    created_at: String,
+    #[openapi(write_only)]
+    password: String,
+    #[serde(skip_serializing)]
+    invite_code: String,
+    #[deprecated]
+    nickname: Option<String>,
+    #[allow(deprecated)]
+    #[openapi(deprecated, read_only)]
+    legacy: Option<LegacyUser>,
+}
+
+#[openapi]
+async fn create_user(_body: User) -> () {}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .post("/user", create_user);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("17-access.yaml"));
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+
+#[derive(OaSchema, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Login {
+    username: String,
+    password: String,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+#[openapi(additional_properties = true)]
+pub struct Metadata {
+    source: String,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Session {
+    token: String,
+    metadata: Metadata,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Profile {
+    name: String,
+    #[serde(flatten)]
+    extra: HashMap<String, String>,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+#[serde(tag = "method")]
+pub enum Credentials {
+    Password(Login),
+    Token { token: String },
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub enum Event {
+    Click { x: i32, y: i32 },
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Audit {
+    at: String,
+    #[serde(flatten)]
+    event: Event,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum Owner {
+    User { id: i32 },
+    Team { id: i32 },
+}
+
+/// The variants of a flattened enum are checked on their own, so they stay open to the other
+/// fields, like `title`.
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Folder {
+    title: String,
+    #[serde(flatten)]
+    owner: Owner,
+}
+
+#[openapi]
+async fn login(_body: Login) -> Session {
+    unimplemented!()
+}
+
+#[openapi]
+async fn update_profile(_body: Profile) -> () {}
+
+#[openapi]
+async fn authenticate(_body: Credentials) -> Audit {
+    unimplemented!()
+}
+
+#[openapi]
+async fn create_folder(_body: Folder) -> () {}
+
+#[openapi]
+async fn set_owner(_body: Owner) -> () {}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .post("/login", login)
+        .additional_properties(false)
+        .post("/profile", update_profile)
+        .post("/authenticate", authenticate)
+        .post("/folder", create_folder);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("18-additional-properties.yaml"));
+
+    // A variant closed for an earlier route is opened again once a route flattens its enum.
+    let server = Server::none()
+        .additional_properties(false)
+        .post("/owner", set_owner);
+    let closed = serde_yaml::to_string(&server.openapi.schemas()["Owner_User"]).unwrap();
+    assert!(closed.contains("additionalProperties: false"), "{}", closed);
+    let server = server.post("/folder", create_folder);
+    let open = serde_yaml::to_string(&server.openapi.schemas()["Owner_User"]).unwrap();
+    assert!(!open.contains("additionalProperties"), "{}", open);
+}
@@ -0,0 +1,41 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::num::NonZeroU64;
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Tag {
+    name: String,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Inventory {
+    id: NonZeroU64,
+    code: char,
+    small: i8,
+    big: u128,
+    label: Cow<'static, str>,
+    tags: HashSet<String>,
+    sorted_tags: BTreeSet<i32>,
+    counts: BTreeMap<String, u32>,
+    by_id: HashMap<u64, Tag>,
+    queue: VecDeque<Tag>,
+    rgb: [u8; 3],
+    point: (f64, f64),
+    entry: (String, i64),
+    owner: Box<Tag>,
+}
+
+#[openapi]
+async fn update_inventory(_body: Inventory) -> Inventory {
+    unimplemented!()
+}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .post("/inventory", update_inventory);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("19-collections.yaml"));
+}
@@ -0,0 +1,32 @@
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Address {
+    street: String,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct UpdateContact {
+    name: Option<String>,
+    home: Option<Address>,
+    /// Where to send mail.
+    mailing: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nickname: Option<Option<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    work: Option<Option<Address>>,
+}
+
+#[openapi]
+async fn update_contact(_body: UpdateContact) -> Address {
+    unimplemented!()
+}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .post("/contact", update_contact);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("20-nullable.yaml"));
+}
@@ -0,0 +1,25 @@
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Event {
+    name: String,
+    payload: Value,
+    labels: Map<String, Value>,
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+#[openapi]
+async fn track(_body: Event) -> Value {
+    unimplemented!()
+}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .post("/track", track);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("21-any.yaml"));
+}
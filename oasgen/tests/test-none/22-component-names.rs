@@ -0,0 +1,70 @@
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+
+mod billing {
+    use super::*;
+
+    #[derive(OaSchema, Serialize, Deserialize)]
+    pub struct Invoice {
+        pub amount: i64,
+    }
+}
+
+mod admin {
+    use super::*;
+
+    #[derive(OaSchema, Serialize, Deserialize)]
+    pub struct Invoice {
+        pub approved: bool,
+    }
+
+    #[derive(OaSchema, Serialize, Deserialize)]
+    #[openapi(rename = "AdminInvoice")]
+    pub struct RenamedInvoice {
+        pub approved: bool,
+    }
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Shape {
+    Circle { radius: f64 },
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+#[allow(non_camel_case_types)]
+pub struct Shape_Circle {
+    pub radius: f64,
+}
+
+#[openapi]
+async fn draw(_shape: Shape, _circle: Shape_Circle) -> () {}
+
+#[openapi]
+async fn pay(_body: billing::Invoice) -> admin::RenamedInvoice {
+    unimplemented!()
+}
+
+#[openapi]
+async fn approve(_body: admin::Invoice) -> () {}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .post("/pay", pay);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("22-component-names.yaml"));
+
+    let collision = std::panic::catch_unwind(|| {
+        Server::none()
+            .post("/pay", pay)
+            .post("/approve", approve)
+    });
+    let message = *collision.err().unwrap().downcast::<String>().unwrap();
+    assert!(message.starts_with("Component name `Invoice` is used by both"), "{}", message);
+
+    // The components of tagged enum variants collide with types of the same name, too.
+    let collision = std::panic::catch_unwind(|| Server::none().post("/draw", draw));
+    let message = *collision.err().unwrap().downcast::<String>().unwrap();
+    assert!(message.starts_with("Component name `Shape_Circle` is used by both"), "{}", message);
+}
@@ -0,0 +1,56 @@
+use oasgen::{OaSchema, Server, openapi};
+use serde::{Deserialize, Serialize};
+
+mod billing {
+    use super::*;
+
+    #[derive(OaSchema, Serialize, Deserialize)]
+    pub struct Invoice {
+        pub amount: i64,
+        pub lines: Vec<Line>,
+    }
+
+    #[derive(OaSchema, Serialize, Deserialize)]
+    pub struct Line {
+        pub description: String,
+    }
+}
+
+mod admin {
+    use super::*;
+
+    #[derive(OaSchema, Serialize, Deserialize)]
+    pub struct Invoice {
+        pub approved: bool,
+    }
+
+    #[derive(OaSchema, Serialize, Deserialize)]
+    #[openapi(rename = "AdminNote")]
+    pub struct Note {
+        pub text: String,
+    }
+}
+
+#[openapi]
+async fn pay(_body: billing::Invoice) -> admin::Note {
+    unimplemented!()
+}
+
+#[openapi]
+async fn approve(_body: admin::Invoice) -> () {}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .qualify_component_names()
+        .post("/pay", pay)
+        .post("/approve", approve);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("23-qualified-names.yaml"));
+
+    // Other servers keep the plain names.
+    let server = Server::none()
+        .post("/pay", pay);
+    let names = server.openapi.schemas().keys().collect::<Vec<_>>();
+    assert_eq!(names, ["Invoice", "Line", "AdminNote"]);
+}
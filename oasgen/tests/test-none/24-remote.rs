@@ -0,0 +1,56 @@
+use oasgen::{OaSchema, Schema, Server, openapi};
+use oasgen::core::Remote;
+use serde::{Deserialize, Serialize};
+
+/// Stands in for a crate we don't control.
+mod other {
+    use serde::{Deserialize, Serialize};
+
+    pub struct Money {
+        pub amount: i64,
+        pub currency: String,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Rate(pub String);
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+#[serde(remote = "other::Money")]
+pub struct MoneyDef {
+    amount: i64,
+    currency: String,
+}
+
+#[derive(OaSchema, Serialize, Deserialize)]
+pub struct Order {
+    #[serde(with = "MoneyDef")]
+    #[openapi(as = MoneyDef)]
+    total: other::Money,
+    #[openapi(as = Remote<other::Rate>)]
+    rate: other::Rate,
+}
+
+#[openapi]
+async fn create_order(_body: Order) -> () {}
+
+fn main() {
+    use pretty_assertions::assert_eq;
+    let server = Server::none()
+        .override_schema::<other::Rate>(Schema::new_string().with_format("decimal"))
+        .post("/order", create_order);
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("24-remote.yaml"));
+
+    // The schema can also be given after the route.
+    let server = Server::none()
+        .post("/order", create_order)
+        .override_schema::<other::Rate>(Schema::new_string().with_format("decimal"));
+    let spec = serde_yaml::to_string(server.openapi.schemas()).unwrap();
+    assert_eq!(spec.trim(), include_str!("24-remote.yaml"));
+
+    // Without one, freezing the spec fails.
+    let missing = std::panic::catch_unwind(|| Server::none().post("/order", create_order).freeze());
+    let message = *missing.err().unwrap().downcast::<String>().unwrap();
+    assert!(message.starts_with("No schema for `trybuild"), "{}", message);
+}